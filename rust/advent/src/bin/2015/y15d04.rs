@@ -1,5 +1,4 @@
-use advent::utilities::get_input::get_input;
-use md5::{Digest, Md5};
+use advent::utilities::{get_input::get_input, hashes::{mine, zero_prefix}};
 use utilities::structs::stopwatch::{ReportDuration, Stopwatch};
 
 type Input<'a> = &'a str;
@@ -16,20 +15,10 @@ fn main() {
 }
 
 fn solve(salt: Input, digit_length: usize) -> Output {
-    let mut hasher = Md5::new();
-    let mut buf = [0u8; 32];
-    (1..)
-        .find(|i| {
-            let message = format!("{}{}", salt, i);
-            hasher.update(&message);
-            base16ct::lower::encode_str(&hasher.finalize_reset(), &mut buf)
-                .unwrap()
-                .as_bytes()
-                .iter()
-                .take(digit_length)
-                .all(|b| *b == b'0')
-        })
+    mine(salt, 0, |digest| zero_prefix(digest, digit_length))
+        .find(|&(i, _)| i > 0)
         .unwrap()
+        .0
 
 }
 fn part1(salt: Input) -> Output {
@@ -1,6 +1,5 @@
-use advent::utilities::get_input::get_input;
+use advent::utilities::{get_input::get_input, hashes::{mine, nibble, zero_prefix}};
 use itertools::Itertools;
-use md5::{Digest, Md5};
 use utilities::structs::stopwatch::{ReportDuration, Stopwatch};
 
 type Output = String;
@@ -17,36 +16,36 @@ fn main() {
 }
 
 fn parse_input(seed: &str) -> impl Iterator<Item = [u8; 16]> + Clone {
-    let mut hasher = Md5::new();
-    (0..)
-        .map(move |i| {
-            let data = format!("{}{}", seed, i);
-            Digest::update(&mut hasher, &data);
-            Digest::finalize_reset(&mut hasher).into()
-        })
-        .filter(|digest: &[u8; 16]| digest[0] == 0 && digest[1] == 0 && digest[2] < 16)
+    // the search is the slow part, so find enough interesting hashes for both parts up front
+    let mut found = Vec::new();
+    let mut positions = [false; 8];
+    for (_, digest) in mine(seed, 0, |digest| zero_prefix(digest, 5)) {
+        if let Some(seen) = positions.get_mut(nibble(&digest, 5) as usize) {
+            *seen = true;
+        }
+        found.push(digest);
+        if found.len() >= 8 && positions.iter().all(|&seen| seen) {
+            break;
+        }
+    }
+    found.into_iter()
+}
+
+fn hex_digit(hash: &[u8; 16], index: usize) -> char {
+    char::from_digit(nibble(hash, index) as u32, 16).unwrap()
 }
 
 fn part1(hashes: impl Iterator<Item = [u8; 16]>) -> Output {
-    hashes
-        .map(|hash| {
-            let b = hash[2] & 15;
-            (if b < 10 { b + 48 } else { b + 87 }) as char
-        })
-        .take(8)
-        .collect()
+    hashes.map(|hash| hex_digit(&hash, 5)).take(8).collect()
 }
 
 fn part2(hashes: impl Iterator<Item = [u8; 16]>) -> Output {
     hashes
-        .filter(|hash| hash[2] & 15 < 8)
-        .unique_by(|hash| hash[2] & 15)
+        .filter(|hash| nibble(hash, 5) < 8)
+        .unique_by(|hash| nibble(hash, 5))
         .take(8)
-        .sorted_unstable_by_key(|hash| hash[2] & 15)
-        .map(|hash| {
-            let b = hash[3] >> 4;
-            (if b < 10 { b + 48 } else { b + 87 }) as char
-        })
+        .sorted_unstable_by_key(|hash| nibble(hash, 5))
+        .map(|hash| hex_digit(&hash, 6))
         .collect()
 }
 
@@ -58,7 +57,7 @@ fn default() {
     assert_eq!("1050cbbd".to_string(), part2(input));
 }
 
-// Input parsed (18μs)
-// 1. 4543c154 (1.468s)
-// 2. 1050cbbd (3.932s)
-// Total: 5.400s
+// Input parsed (5.265s)
+// 1. 4543c154 (33μs)
+// 2. 1050cbbd (9μs)
+// Total: 5.265s
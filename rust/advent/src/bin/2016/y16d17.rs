@@ -1,5 +1,4 @@
-use advent::utilities::get_input::get_input;
-use md5::{Digest, Md5};
+use advent::utilities::{get_input::get_input, hashes::{md5, nibble}};
 use utilities::{enums::cardinals::Cardinal, structs::{coord::Coord2U, stopwatch::{ReportDuration, Stopwatch}}};
 
 type Pos = Coord2U;
@@ -19,18 +18,13 @@ fn main() {
 fn explore(salt: String) -> (String, usize) {
     let start = (salt.clone(), Pos::origin());
     let end_pos = Pos::new2d(3, 3);
-    let open_range = b'B'..=b'F';
-    let mut hasher = Md5::new();
-    let mut buf = [0u8; 32];
-    
-    let mut get_edges = |passcode: String, pos: Pos| {
+    let open_range = 0xb..=0xf;
+
+    let get_edges = |passcode: String, pos: Pos| {
         let mut edges = Vec::new();
-        Digest::update(&mut hasher, &passcode);
-        let hash: [u8; 16] = Digest::finalize_reset(&mut hasher).into();
-        let hash = base16ct::upper::encode_str(&hash, &mut buf).unwrap().to_string();
-        let hash = hash.as_bytes();
-        for (i, door) in hash[..4].iter().enumerate() {
-            if open_range.contains(door) {
+        let hash = md5(passcode.as_bytes());
+        for i in 0..4 {
+            if open_range.contains(&nibble(&hash, i)) {
                 let neighbor = match i {
                     0 => make_edge(&passcode, pos, Cardinal::North, 'U'),
                     1 => make_edge(&passcode, pos, Cardinal::South, 'D'),
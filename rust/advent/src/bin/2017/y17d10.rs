@@ -1,5 +1,4 @@
-use advent::utilities::{get_input::get_input, hashes::{dense_hash, knot_hash}};
-use itertools::Itertools;
+use advent::utilities::{get_input::get_input, hashes::{knot_hash, sparse_hash, to_hex}};
 use utilities::{
     parsing::get_numbers::ContainsNumbers,
     structs::stopwatch::{ReportDuration, Stopwatch},
@@ -19,21 +18,12 @@ fn main() {
 
 fn part1(input: Input) -> usize {
     let lengths: Vec<usize> = input.get_numbers().collect();
-    let ring = (0..256).collect_vec();
-    let mut knot_hash = knot_hash(ring, &lengths, 0);
-    let knot_len = knot_hash.len();
-    let shift = lengths.iter().sum::<usize>()
-        + (1..lengths.len()).sum::<usize>();
-    knot_hash.rotate_right(shift % knot_len);
-    knot_hash[0] * knot_hash[1]
+    let ring = sparse_hash::<256, _>(lengths, 1);
+    ring[0] as usize * ring[1] as usize
 }
 
 fn part2(input: Input) -> String {
-    let lengths = input.as_bytes().iter()
-        .map(|&b| b as usize)
-        .chain([17, 31, 73, 47, 23].into_iter())
-        .collect_vec();
-    dense_hash(&lengths)
+    to_hex(&knot_hash(input.as_bytes()))
 }
 
 #[test]
@@ -1,5 +1,5 @@
 use advent::utilities::get_input::get_input;
-use utilities::structs::{hexagon::Hexagon, stopwatch::{ReportDuration, Stopwatch}};
+use utilities::structs::{hexagon::{FlatDirection, Hexagon}, stopwatch::{ReportDuration, Stopwatch}};
 
 type Input = Vec<Hexagon>;
 type Output = usize;
@@ -16,22 +16,22 @@ fn main() {
 }
 
 fn parse_input(input: &str) -> Input {
-    input.split(',')
-        .map(|dir| dir.into())
+    FlatDirection::parse_moves(input).unwrap()
+        .into_iter()
         .scan(Hexagon::origin(), |acc, dir| {
-            *acc = acc.hex_at(dir);
+            *acc = acc.neighbor(dir);
             Some(*acc)
         })
         .collect()
 }
 
 fn part1(path: &Input) -> Output {
-    path.last().unwrap().distance(Hexagon::origin())
+    path.last().unwrap().length()
 }
 
 fn part2(path: &Input) -> Output {
     path.iter()
-        .map(|hex| hex.distance(Hexagon::origin()))
+        .map(Hexagon::length)
         .max()
         .unwrap()
 }
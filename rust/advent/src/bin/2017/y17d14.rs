@@ -1,4 +1,4 @@
-use advent::utilities::{get_input::get_input, hashes::dense_hash};
+use advent::utilities::{get_input::get_input, hashes::{bits, knot_hash}};
 use itertools::Itertools;
 use utilities::structs::{grid::Grid, stopwatch::{ReportDuration, Stopwatch}};
 
@@ -19,16 +19,8 @@ fn main() {
 fn parse_input(input: &str) -> Input {
     (0..128)
         .map(|i| {
-            let mut prep = input.to_string();
-            prep.push('-');
-            prep.push_str(&i.to_string());
-            let prep = prep.as_bytes().iter().copied()
-                .chain([17, 31, 73, 47, 23].into_iter())
-                .map(|b| b as usize)
-                .collect_vec();
-            dense_hash(&prep).chars()
-                .map(|c| format!("{:04b}", c.to_digit(16).unwrap()))
-                .collect()
+            let hash = knot_hash(format!("{input}-{i}").as_bytes());
+            bits(&hash).map(|used| if used { '1' } else { '0' }).collect()
         })
         .collect()
 }
@@ -3,10 +3,11 @@
 
 use advent::utilities::get_input::get_input;
 use itertools::Itertools;
+use utilities::math::modular::Affine;
 use utilities::structs::stopwatch::{ReportDuration, Stopwatch};
 
 type Input<'a> = &'a str;
-type Int = i128;
+type Int = i64;
 
 fn main() {
     let mut stopwatch = Stopwatch::new();
@@ -18,103 +19,31 @@ fn main() {
     println!("Total: {}", stopwatch.stop().report());
 }
 
-struct Deck {
-    a: Int,
-    c: Int,
-    m: Int,
-}
-
-impl Deck {
-    fn new(input: Input, m: Int) -> Self {
-        input.lines()
-            .map(|line| {
-                let tokens = line.split(' ').collect_vec();
-                match tokens[ .. ] {
-                    [_, "into", _, _] => Self { a: m - 1, c: m - 1, m },
-                    [_, "with", _, n] => {
-                        let n: Int = n.parse().unwrap();
-                        let a = (m + n % m) % m;
-                        Self { a, c: 0, m }
-                    },
-                    ["cut", n] => {
-                        let n: Int = n.parse().unwrap();
-                        let c = (m - n % m) % m;
-                        Self { a: 1, c, m }
-                    }
-                    _ => unreachable!(),
-                }
-            })
-            .reduce(|a, b| a.compose(&b))
-            .unwrap()
-    }
-
-    fn compose(&self, other: &Self) -> Self {
-        Self {
-            a: (self.a * other.a) % self.m,
-            c: (other.a * self.c + other.c) % self.m,
-            m: self.m,
-        }
-    }
-
-    fn shuffle(&self, index: Int) -> Int {
-        (self.a * index + self.c) % self.m
-    }
-
-    fn inverse(&self) -> Self {
-        let m = self.m;
-        let a = mod_inv(self.a, m);
-        let c = m - (a * self.c) % m;
-        Self { a, c, m }
-    }
-
-    fn power(&self, e: Int) -> Self {
-        let m = self.m;
-        let a = mod_pow(self.a, e, m);
-        let c = (((a - 1) * mod_inv(self.a - 1, m) % m) * self.c) % m;
-        Self { a, c, m }
-    }
-}
-
-fn mod_inv(n: Int, m: Int) -> Int {
-    let mut t = 0;
-    let mut new_t = 1;
-    let mut r = m;
-    let mut new_r = n;
-
-    while new_r != 0 {
-        let quotient = r / new_r;
-        (t, new_t) = (new_t, t - quotient * new_t);
-        (r, new_r) = (new_r, r - quotient * new_r);
-    }
-
-    t + m
-}
-
-fn mod_pow(n: Int, mut e: Int, m: Int) -> Int {
-    let mut b = n;
-    let mut c = 1;
-
-    while e > 0 {
-        if e & 1 == 1 {
-            c = (c * b) % m;
-        }
-        b = (b * b) % m;
-        e = e >> 1;
-    }
-
-    c
+fn deck<const M: Int>(input: Input) -> Affine<M> {
+    input.lines()
+        .map(|line| {
+            let tokens = line.split(' ').collect_vec();
+            match tokens[ .. ] {
+                [_, "into", _, _] => Affine::new(-1, -1),
+                [_, "with", _, n] => Affine::new(n.parse().unwrap(), 0),
+                ["cut", n] => Affine::new(1, -n.parse::<Int>().unwrap()),
+                _ => unreachable!(),
+            }
+        })
+        .reduce(|a, b| a.then(&b))
+        .unwrap()
 }
 
 fn part1(input: Input) -> Int {
-    let deck = Deck::new(input, 10_007);
-    deck.shuffle(2019)
+    deck::<10_007>(input).apply(2019)
 }
 
 fn part2(input: Input) -> Int {
-    Deck::new(input, 119_315_717_514_047)
+    deck::<119_315_717_514_047>(input)
         .inverse()
-        .power(101_741_582_076_661)
-        .shuffle(2020)
+        .unwrap()
+        .pow(101_741_582_076_661)
+        .apply(2020)
 }
 
 #[test]
@@ -1,6 +1,7 @@
 use std::iter::successors;
 
 use advent::utilities::get_input::get_input;
+use utilities::math::modular::crt;
 use utilities::structs::stopwatch::{ReportDuration, Stopwatch};
 
 type Input = (Int, Vec<Bus>);
@@ -29,23 +30,6 @@ struct State {
     time: Int,
 }
 
-fn modular_inverse(ni: Int, modulus: Int) -> Int {
-    (1..)
-        .find(|it| (ni % modulus * *it) % modulus == 1)
-        .unwrap()
-}
-
-fn crt(buses: &Vec<Bus>) -> Bus {
-    let n = buses.iter().fold(1, |acc, bus| acc * bus.id);
-    let big_phase: Int = buses.iter()
-        .map(|bus| {
-            let ni = n / bus.id;
-            bus.offset * ni * modular_inverse(ni, bus.id)
-        })
-        .sum();
-    Bus { id: n, offset: big_phase % n }
-}
-
 fn parse_input(input: &str) -> Input {
     let (start, buses) = input.trim_end().split_once('\n').unwrap();
     let start = start.parse().unwrap();
@@ -77,8 +61,11 @@ fn part1(input: &Input) -> Output {
 
 fn part2(input: &Input) -> Output {
     let (_, buses) = input;
-    let bus = crt(buses);
-    bus.id - bus.offset
+    let congruences: Vec<(i64, i64)> = buses.iter()
+        .map(|bus| (-(bus.offset as i64), bus.id as i64))
+        .collect();
+    let (time, _) = crt(&congruences).unwrap();
+    time as Output
 }
 
 #[test]
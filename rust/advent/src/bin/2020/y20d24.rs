@@ -1,9 +1,7 @@
 use advent::utilities::get_input::get_input;
-use lazy_regex::regex;
-use rustc_hash::{FxHashMap, FxHashSet};
-use utilities::{enums::intercardinals::Intercardinal, structs::{hexagon::Hexagon, stopwatch::{ReportDuration, Stopwatch}}};
+use utilities::{automaton::Automaton, structs::{hexagon::{HexCells, HexGrid, Hexagon, PointyDirection}, stopwatch::{ReportDuration, Stopwatch}}};
 
-type Input = FxHashSet<Hexagon>;
+type Input = HexGrid<bool>;
 type Output = usize;
 
 fn main() {
@@ -18,57 +16,27 @@ fn main() {
 }
 
 fn parse_input(input: &str) -> Input {
-    let rx = regex!("(nw|ne|sw|se|w|e)");
-    let rules = input.lines()
-        .map(|line| {
-            rx.find_iter(line).map(|dir| match dir.as_str() {
-                "w" => Intercardinal::North,
-                "nw" => Intercardinal::Northeast,
-                "ne" => Intercardinal::Southeast,
-                "e" => Intercardinal::South,
-                "se" => Intercardinal::Southwest,
-                "sw" => Intercardinal::Northwest,
-                _ => unreachable!()
-            })
-        });
-    let mut flipped_tiles = FxHashSet::default();
-
-    for rule in rules {
-        let tile = rule.fold(Hexagon::origin(), |acc, dir| {
-            acc.hex_at(dir)
-        });
-
-        if flipped_tiles.contains(&tile) {
-            flipped_tiles.remove(&tile);
-        } else {
-            flipped_tiles.insert(tile);
-        }
+    let mut floor = HexGrid::new(false);
+    for line in input.lines() {
+        let tile = Hexagon::origin().walk(PointyDirection::parse_moves(line).unwrap());
+        let flipped = !floor.get_hex(tile).is_some_and(|&black| black);
+        floor.insert_hex(tile, flipped);
     }
-    flipped_tiles
+    floor
 }
 
-fn part1(flipped_tiles: Input) -> Output {
-    flipped_tiles.len()
+fn part1(floor: Input) -> Output {
+    floor.len()
 }
 
-fn part2(mut flipped_tiles: Input) -> Output {
-    let mut candidates: FxHashMap<Hexagon, usize> = FxHashMap::default();
-    for _day in 0..100 {
-        for tile in flipped_tiles.iter() {
-            for adj in tile.adjacent() {
-                candidates.entry(adj)
-                    .and_modify(|n| { *n += 1; })
-                    .or_insert(1);
-            }
-        }
-        flipped_tiles = candidates.drain()
-            .filter(|(candidate, occurrences)| {
-                *occurrences == 2 || (*occurrences == 1 && flipped_tiles.contains(candidate))
-            })
-            .map(|(confirmed, _)| confirmed)
-            .collect();
-    }
-    flipped_tiles.len()
+fn part2(floor: Input) -> Output {
+    let rule = |_, &black: &bool, neighbors: &[Option<&bool>]| {
+        let adjacent = neighbors.iter().flatten().filter(|&&&n| n).count();
+        adjacent == 2 || (black && adjacent == 1)
+    };
+    let mut automaton = Automaton::new(floor, Hexagon::neighborhood(), rule);
+    automaton.run(100);
+    automaton.state.len()
 }
 
 #[test]
@@ -1,32 +1,295 @@
-use itertools::Itertools;
-
-pub fn dense_hash(lengths: &[usize]) -> String {
-    let ring: Vec<usize> = (0..256).collect();   
-    let shift_sum = lengths.iter().sum::<usize>() * 64;
-    let skip_sum = lengths.len() * 64;
-    let mut reshifted = (0..64).fold(ring, |acc, i| knot_hash(acc, lengths, i * lengths.len()));
-    let reshifted_len = reshifted.len();
-    let total_skips = (1..skip_sum).sum::<usize>();
-    reshifted.rotate_right((total_skips + shift_sum) % reshifted_len);
-
-    let dense_hash = reshifted.into_iter().chunks(16).into_iter()
-        .map(|chunk| {
-            let reduction = chunk.reduce(|acc, i| acc ^ i).unwrap();
-            format!("{:02x}", reduction)
-        })
-        .collect();
-    dense_hash
-}
-
-pub fn knot_hash(ring: Vec<usize>, lengths: &[usize], skip: usize) -> Vec<usize> {
-    lengths.iter().enumerate().fold(ring, |acc, (index, &length)| {
-        let reverse_part: Vec<usize> = acc[..length].iter().rev().copied().collect();
-        let mut knot: Vec<usize> = reverse_part.iter()
-            .chain(acc.iter().skip(reverse_part.len()))
-            .copied()
-            .collect();
-        let knot_len = knot.len();
-        knot.rotate_left((length + skip + index) % knot_len);
-        knot
+use md5::{Digest, Md5};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+/// Lengths appended to the input bytes of every full knot hash.
+pub const KNOT_SUFFIX: [usize; 5] = [17, 31, 73, 47, 23];
+
+/// Ties `rounds` rounds of knots into a ring of marks `0..N`, reversing each span in place and
+/// carrying the position and skip size from one round to the next. The ring is returned as
+/// marked, so no rotation back to the start is needed. The lengths are iterated once per round.
+pub fn sparse_hash<const N: usize, I>(lengths: I, rounds: usize) -> [u8; N]
+where
+    I: IntoIterator<Item = usize> + Clone,
+{
+    let mut ring: [u8; N] = std::array::from_fn(|i| i as u8);
+    let (mut position, mut skip) = (0, 0);
+    for _ in 0..rounds {
+        for length in lengths.clone() {
+            for i in 0..length / 2 {
+                ring.swap((position + i) % N, (position + length - 1 - i) % N);
+            }
+            position = (position + length + skip) % N;
+            skip += 1;
+        }
+    }
+    ring
+}
+
+/// Folds a sparse hash into sixteen bytes by XORing each block of sixteen.
+pub fn dense_hash(sparse: &[u8; 256]) -> [u8; 16] {
+    std::array::from_fn(|block| {
+        sparse[block * 16..(block + 1) * 16]
+            .iter()
+            .fold(0, |acc, &b| acc ^ b)
     })
 }
+
+/// The full knot hash of `input`: its bytes plus the standard suffix as lengths, tied for 64
+/// rounds and then condensed, without allocating.
+pub fn knot_hash(input: &[u8]) -> [u8; 16] {
+    let lengths = input.iter().map(|&b| b as usize).chain(KNOT_SUFFIX);
+    dense_hash(&sparse_hash(lengths, 64))
+}
+
+/// The `index`th four-bit digit of `bytes`, most significant first.
+pub fn nibble(bytes: &[u8], index: usize) -> u8 {
+    let byte = bytes[index / 2];
+    if index.is_multiple_of(2) { byte >> 4 } else { byte & 15 }
+}
+
+/// Every nibble of `bytes` in order.
+pub fn nibbles(bytes: &[u8]) -> impl Iterator<Item = u8> + '_ {
+    bytes.iter().flat_map(|&b| [b >> 4, b & 15])
+}
+
+/// Every bit of `bytes` in order, most significant first.
+pub fn bits(bytes: &[u8]) -> impl Iterator<Item = bool> + '_ {
+    bytes
+        .iter()
+        .flat_map(|&b| (0..8).rev().map(move |shift| b >> shift & 1 == 1))
+}
+
+/// Writes `bytes` as lowercase hex into `buf`, which must hold twice as many bytes, without
+/// allocating.
+pub fn write_hex<'a>(bytes: &[u8], buf: &'a mut [u8]) -> &'a [u8] {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    for (slot, n) in buf.iter_mut().zip(nibbles(bytes)) {
+        *slot = DIGITS[n as usize];
+    }
+    &buf[..bytes.len() * 2]
+}
+
+/// `bytes` as a lowercase hex string.
+pub fn to_hex(bytes: &[u8]) -> String {
+    nibbles(bytes)
+        .map(|n| char::from_digit(n as u32, 16).unwrap())
+        .collect()
+}
+
+/// MD5 digest of `data`.
+pub fn md5(data: &[u8]) -> [u8; 16] {
+    Md5::digest(data).into()
+}
+
+/// MD5 of `data`, then rehashed `stretch` more times, each time hashing the lowercase hex of
+/// the previous digest, as in y16d14's key stretching.
+pub fn stretched_md5(hasher: &mut Md5, data: &[u8], stretch: usize) -> [u8; 16] {
+    Digest::update(hasher, data);
+    let mut digest: [u8; 16] = hasher.finalize_reset().into();
+    let mut buf = [0; 32];
+    for _ in 0..stretch {
+        Digest::update(hasher, write_hex(&digest, &mut buf));
+        digest = hasher.finalize_reset().into();
+    }
+    digest
+}
+
+/// Whether the hex form of `digest` starts with `count` zeros.
+pub fn zero_prefix(digest: &[u8], count: usize) -> bool {
+    (0..count).all(|i| nibble(digest, i) == 0)
+}
+
+/// The first nibble repeated `length` times in a row in `digest`, if any.
+pub fn first_run(digest: &[u8], length: usize) -> Option<u8> {
+    runs(digest, length).next()
+}
+
+/// The nibble of every run of at least `length` repeats in `digest`, in order. Each run counts
+/// once however long it is.
+pub fn runs(digest: &[u8], length: usize) -> impl Iterator<Item = u8> + '_ {
+    let mut count = 0;
+    let mut previous = None;
+    nibbles(digest).filter(move |&n| {
+        count = if previous == Some(n) { count + 1 } else { 1 };
+        previous = Some(n);
+        count == length
+    })
+}
+
+/// Hashes `salt` followed by each index from zero, stretched `stretch` times, and yields the
+/// index and digest of every one that satisfies `condition`, in order. Hashing runs in parallel
+/// one block of indices at a time, so the iterator can be stopped early like any other.
+pub fn mine<F>(
+    salt: &str,
+    stretch: usize,
+    condition: F,
+) -> impl Iterator<Item = (usize, [u8; 16])> + use<F>
+where
+    F: Fn(&[u8; 16]) -> bool + Sync,
+{
+    let block = ((1 << 16) / (stretch + 1)).max(256);
+    let salt = salt.as_bytes().to_vec();
+    (0..).step_by(block).flat_map(move |start| {
+        (start..start + block)
+            .into_par_iter()
+            .map_init(
+                || (Md5::new(), salt.clone()),
+                |(hasher, message), index| {
+                    message.truncate(salt.len());
+                    message.extend_from_slice(index.to_string().as_bytes());
+                    let digest = stretched_md5(hasher, message, stretch);
+                    condition(&digest).then_some((index, digest))
+                },
+            )
+            .flatten()
+            .collect::<Vec<_>>()
+    })
+}
+
+/// Every salted digest in order, for searches that look at each one.
+pub fn salted_hashes(salt: &str, stretch: usize) -> impl Iterator<Item = [u8; 16]> {
+    mine(salt, stretch, |_| true).map(|(_, digest)| digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The knot hash as first written, allocating a new ring for every length.
+    fn reference_sparse(lengths: &[usize], rounds: usize) -> Vec<u8> {
+        let mut ring: Vec<u8> = (0..=255).collect();
+        let (mut position, mut skip) = (0, 0);
+        for _ in 0..rounds {
+            for &length in lengths {
+                ring.rotate_left(position);
+                ring[..length].reverse();
+                ring.rotate_right(position);
+                position = (position + length + skip) % 256;
+                skip += 1;
+            }
+        }
+        ring
+    }
+
+    #[test]
+    fn knots() {
+        // y17d10 examples
+        let ring = sparse_hash::<5, _>([3, 4, 1, 5], 1);
+        assert_eq!([3, 4, 2, 1, 0], ring);
+        assert_eq!(12, ring[0] as usize * ring[1] as usize);
+        let hashes: Vec<String> = ["", "AoC 2017", "1,2,3", "1,2,4"]
+            .iter()
+            .map(|input| to_hex(&knot_hash(input.as_bytes())))
+            .collect();
+        assert_eq!(
+            vec![
+                "a2582a3a0e66e6e86e3812dcb672a272",
+                "33efeb34ea91902bb2f59c9920caa6cd",
+                "3efbe78a8d82f29979031a4aa0b16a9d",
+                "63960835bcdc130f0b66d7ff4f6a5a8e",
+            ],
+            hashes
+        );
+
+        let mut seed = 12345usize;
+        for count in 0..40 {
+            let lengths: Vec<usize> = (0..count)
+                .map(|_| {
+                    seed = seed
+                        .wrapping_mul(6364136223846793005)
+                        .wrapping_add(1442695040888963407);
+                    (seed >> 33) % 257
+                })
+                .collect();
+            for rounds in [1, 2, 64] {
+                assert_eq!(
+                    reference_sparse(&lengths, rounds),
+                    sparse_hash::<256, _>(lengths.iter().copied(), rounds)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn disk_grid() {
+        // y17d14 example
+        let rows: Vec<[u8; 16]> = (0..128)
+            .map(|row| knot_hash(format!("flqrgnkx-{row}").as_bytes()))
+            .collect();
+        let top: String = bits(&rows[0])
+            .take(8)
+            .map(|used| if used { '#' } else { '.' })
+            .collect();
+        assert_eq!("##.#.#..", top);
+        assert_eq!(
+            8108,
+            rows.iter().flat_map(|row| bits(row)).filter(|&b| b).count()
+        );
+        let ones: u32 = rows.iter().flatten().map(|b| b.count_ones()).sum();
+        assert_eq!(8108, ones);
+    }
+
+    #[test]
+    fn hex() {
+        let bytes = [0x00, 0x1f, 0xa2, 0xff];
+        assert_eq!("001fa2ff", to_hex(&bytes));
+        let mut buf = [0; 8];
+        assert_eq!(b"001fa2ff", write_hex(&bytes, &mut buf));
+        assert_eq!(
+            vec![0, 0, 1, 15, 10, 2, 15, 15],
+            nibbles(&bytes).collect::<Vec<_>>()
+        );
+        assert_eq!(10, nibble(&bytes, 4));
+        assert!(zero_prefix(&bytes, 2) && !zero_prefix(&bytes, 3));
+        assert_eq!(
+            bytes.iter().map(|b| b.count_ones() as usize).sum::<usize>(),
+            bits(&bytes).filter(|&b| b).count()
+        );
+        assert_eq!(Some(0), first_run(&bytes, 2));
+        assert_eq!(vec![0, 15], runs(&bytes, 2).collect::<Vec<_>>());
+        assert_eq!(None, first_run(&bytes, 3));
+    }
+
+    #[test]
+    fn md5_vectors() {
+        assert_eq!("d41d8cd98f00b204e9800998ecf8427e", to_hex(&md5(b"")));
+        assert_eq!("900150983cd24fb0d6963f7d28e17f72", to_hex(&md5(b"abc")));
+        // y16d17: up, down and left are open for the passcode hijkl
+        let doors: Vec<bool> = (0..4).map(|i| nibble(&md5(b"hijkl"), i) > 10).collect();
+        assert_eq!(vec![true, true, true, false], doors);
+
+        // y16d14 examples, plain and stretched
+        let mut hasher = Md5::new();
+        assert_eq!(
+            Some(8),
+            first_run(&stretched_md5(&mut hasher, b"abc18", 0), 3)
+        );
+        assert_eq!(
+            "a107ff634856bb300138cac6568c0f24",
+            to_hex(&stretched_md5(&mut hasher, b"abc0", 2016))
+        );
+        let plain: Vec<[u8; 16]> = salted_hashes("abc", 0).take(40).collect();
+        assert_eq!(md5(b"abc39"), plain[39]);
+    }
+
+    #[test]
+    fn mining() {
+        // y15d04 example
+        assert_eq!(
+            Some(609043),
+            mine("abcdef", 0, |digest| zero_prefix(digest, 5))
+                .next()
+                .map(|(index, _)| index)
+        );
+        let found: Vec<usize> = mine("abc", 0, |digest| digest[0] == 0)
+            .take(20)
+            .map(|(index, _)| index)
+            .collect();
+        assert!(found.is_sorted());
+        assert!(
+            found
+                .iter()
+                .all(|i| md5(format!("abc{i}").as_bytes())[0] == 0)
+        );
+    }
+}
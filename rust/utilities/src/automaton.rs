@@ -0,0 +1,440 @@
+use rustc_hash::{FxHashSet, FxHasher};
+use std::hash::{Hash, Hasher};
+
+use crate::cycles::{self, Cycle};
+use crate::structs::{coord::Coord, grid::Grid, sparse_grid::SparseGrid};
+
+/// Which cells feed into a cell's rule.
+#[derive(Clone, Debug)]
+pub enum Neighborhood<T, const N: usize> {
+    /// The cells one step away along a single axis.
+    VonNeumann,
+    /// Every cell within one step along every axis, including diagonals.
+    Moore,
+    /// The first cell along each Moore direction that is not transparent, skipping over those
+    /// that are. Directions that leave the world without finding one see `None`.
+    LineOfSight(fn(&T) -> bool),
+    /// Arbitrary relative positions, passed to the rule in the given order, with `None` for any
+    /// that fall outside the world.
+    Offsets(Vec<Coord<i64, N>>),
+}
+
+impl<T, const N: usize> Neighborhood<T, N> {
+    /// Offsets of the cells that can directly influence a cell. For line of sight this is only
+    /// the nearest cell in each direction.
+    pub fn offsets(&self) -> Vec<Coord<i64, N>> {
+        match self {
+            Neighborhood::VonNeumann => Coord::offsets(1, false),
+            Neighborhood::Moore | Neighborhood::LineOfSight(_) => Coord::offsets(1, true),
+            Neighborhood::Offsets(offsets) => offsets.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Update {
+    /// Every cell is computed from the previous generation.
+    #[default]
+    Synchronous,
+    /// Cells are updated one at a time in position order, seeing earlier updates from the same
+    /// generation.
+    InPlace,
+}
+
+/// A world an `Automaton` can run over.
+pub trait Cells<T, const N: usize>: Clone {
+    /// The value at `pos`, or `None` if the position is outside the world.
+    fn cell(&self, pos: Coord<i64, N>) -> Option<&T>;
+
+    /// Whether `pos` lies within the finite part of the world. Line of sight stops here.
+    fn contains(&self, pos: Coord<i64, N>) -> bool;
+
+    /// Positions that may change this generation, in update order. `offsets` is the
+    /// neighborhood, for worlds that only track some of their cells.
+    fn positions(&self, offsets: &[Coord<i64, N>]) -> Vec<Coord<i64, N>>;
+
+    fn set(&mut self, pos: Coord<i64, N>, value: T);
+
+    /// Hash of the cell contents, used to spot repeated generations.
+    fn fingerprint(&self) -> u64;
+
+    fn same_cells(&self, other: &Self) -> bool;
+}
+
+impl<T, const N: usize> Cells<T, N> for Grid<T, N>
+where
+    T: Clone + Hash + Eq,
+{
+    fn cell(&self, pos: Coord<i64, N>) -> Option<&T> {
+        self.get(pos)
+    }
+
+    fn contains(&self, pos: Coord<i64, N>) -> bool {
+        self.get(pos).is_some()
+    }
+
+    fn positions(&self, _: &[Coord<i64, N>]) -> Vec<Coord<i64, N>> {
+        self.coords().map(Coord::<i64, N>::from).collect()
+    }
+
+    fn set(&mut self, pos: Coord<i64, N>, value: T) {
+        if let Some(cell) = self.get_mut(pos) {
+            *cell = value;
+        }
+    }
+
+    fn fingerprint(&self) -> u64 {
+        let mut hasher = FxHasher::default();
+        self.data.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn same_cells(&self, other: &Self) -> bool {
+        self.data == other.data
+    }
+}
+
+/// Sparse worlds only revisit stored cells and their neighbors, so rules must map a background
+/// cell surrounded by background back to the background.
+impl<T, const N: usize> Cells<T, N> for SparseGrid<T, N>
+where
+    T: Clone + Hash + Eq,
+{
+    fn cell(&self, pos: Coord<i64, N>) -> Option<&T> {
+        Some(self.get(pos))
+    }
+
+    fn contains(&self, pos: Coord<i64, N>) -> bool {
+        self.bounds().is_some_and(|(min, max)| {
+            (0..N).all(|axis| (min.0[axis]..=max.0[axis]).contains(&pos.0[axis]))
+        })
+    }
+
+    fn positions(&self, offsets: &[Coord<i64, N>]) -> Vec<Coord<i64, N>> {
+        let mut positions = FxHashSet::default();
+        for (&pos, _) in self.iter() {
+            positions.insert(pos);
+            positions.extend(offsets.iter().map(|&offset| pos - offset));
+        }
+        let mut positions: Vec<_> = positions.into_iter().collect();
+        positions.sort_unstable();
+        positions
+    }
+
+    fn set(&mut self, pos: Coord<i64, N>, value: T) {
+        self.insert(pos, value);
+    }
+
+    fn fingerprint(&self) -> u64 {
+        // combine per-cell hashes with a sum so the result ignores map iteration order
+        self.iter()
+            .map(|cell| {
+                let mut hasher = FxHasher::default();
+                cell.hash(&mut hasher);
+                hasher.finish()
+            })
+            .fold(0, u64::wrapping_add)
+    }
+
+    fn same_cells(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(&pos, value)| other.get_stored(pos) == Some(value))
+    }
+}
+
+/// A cellular automaton over any `Cells` world. The rule receives a cell's position, its current
+/// value and its neighbors' values in neighborhood order, and returns the cell's next value.
+/// Neighbors outside the world are `None`, so entry `i` always belongs to offset `i`.
+#[derive(Clone, Debug)]
+pub struct Automaton<T, W, R, const N: usize> {
+    pub state: W,
+    pub generation: usize,
+    neighborhood: Neighborhood<T, N>,
+    offsets: Vec<Coord<i64, N>>,
+    rule: R,
+    update: Update,
+}
+
+impl<T, W, R, const N: usize> Automaton<T, W, R, N>
+where
+    T: Clone + PartialEq,
+    W: Cells<T, N>,
+    R: Fn(Coord<i64, N>, &T, &[Option<&T>]) -> T,
+{
+    pub fn new(state: W, neighborhood: Neighborhood<T, N>, rule: R) -> Self {
+        let offsets = neighborhood.offsets();
+        Self {
+            state,
+            generation: 0,
+            neighborhood,
+            offsets,
+            rule,
+            update: Update::default(),
+        }
+    }
+
+    pub fn with_update(self, update: Update) -> Self {
+        Self { update, ..self }
+    }
+
+    /// Advances one generation, returning whether any cell changed.
+    pub fn step(&mut self) -> bool {
+        let (next, changed) = self.next_state(&self.state);
+        self.state = next;
+        self.generation += 1;
+        changed
+    }
+
+    /// The generation after `state`, and whether any cell changed.
+    fn next_state(&self, state: &W) -> (W, bool) {
+        let mut next = state.clone();
+        let mut changed = false;
+        for pos in state.positions(&self.offsets) {
+            let source = match self.update {
+                Update::Synchronous => state,
+                Update::InPlace => &next,
+            };
+            let Some(current) = source.cell(pos) else {
+                continue;
+            };
+            let neighbors = self.neighbors(source, pos);
+            let value = (self.rule)(pos, current, &neighbors);
+            if &value != current {
+                changed = true;
+                next.set(pos, value);
+            }
+        }
+        (next, changed)
+    }
+
+    pub fn run(&mut self, generations: usize) {
+        for _ in 0..generations {
+            self.step();
+        }
+    }
+
+    /// Steps until a generation changes nothing, returning the number of that generation.
+    pub fn run_until_stable(&mut self) -> usize {
+        while self.step() {}
+        self.generation
+    }
+
+    /// Steps until a generation repeats, leaving the state at the first repeat. Gives up with
+    /// `None` after `limit` generations, since some worlds never repeat.
+    pub fn find_cycle(&mut self, limit: usize) -> Option<Cycle> {
+        match self.record_until_cycle(limit) {
+            Ok(mut history) => {
+                let last = history.states.len() - 1;
+                let cycle = Cycle {
+                    start: self.generation + history.cycle.start,
+                    length: history.cycle.length,
+                };
+                self.state = history.states.swap_remove(last);
+                self.generation += last;
+                Some(cycle)
+            }
+            Err(state) => {
+                self.state = state;
+                self.generation += limit;
+                None
+            }
+        }
+    }
+
+    /// Advances to `generation` and returns its state, stepping only until the states start to
+    /// repeat. Generations before the current one are not available.
+    pub fn state_at(&mut self, generation: usize) -> W {
+        assert!(
+            generation >= self.generation,
+            "Generation {generation} has already passed."
+        );
+        let steps = generation - self.generation;
+        self.state = match self.record_until_cycle(steps) {
+            Ok(history) => history.nth_state(steps).clone(),
+            Err(state) => state,
+        };
+        self.generation = generation;
+        self.state.clone()
+    }
+
+    /// Records states from the current one until one repeats, or returns the state `limit`
+    /// generations ahead if no repeat comes first. Cycle starts in the history count from the
+    /// current generation.
+    fn record_until_cycle(&self, limit: usize) -> Result<cycles::CycleHistory<W>, W> {
+        cycles::record(
+            self.state.clone(),
+            |state| self.next_state(state).0,
+            W::fingerprint,
+            W::same_cells,
+            limit,
+        )
+    }
+
+    fn neighbors<'a>(&self, source: &'a W, pos: Coord<i64, N>) -> Vec<Option<&'a T>> {
+        match &self.neighborhood {
+            Neighborhood::LineOfSight(transparent) => self
+                .offsets
+                .iter()
+                .map(|&direction| {
+                    let mut current = pos + direction;
+                    while source.contains(current) {
+                        let value = source.cell(current)?;
+                        if !transparent(value) {
+                            return Some(value);
+                        }
+                        current += direction;
+                    }
+                    None
+                })
+                .collect(),
+            _ => self
+                .offsets
+                .iter()
+                .map(|&offset| source.cell(pos + offset))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::coord::Coord2;
+
+    fn life(_: Coord2, &alive: &bool, neighbors: &[Option<&bool>]) -> bool {
+        let count = neighbors.iter().flatten().filter(|&&&on| on).count();
+        count == 3 || (alive && count == 2)
+    }
+
+    fn lights() -> Grid<bool, 2> {
+        Grid::new2d_map_str(
+            ".#.#.#\n...##.\n#....#\n..#...\n#.#..#\n####..",
+            |c| c == '#',
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn game_of_life() {
+        let mut automaton = Automaton::new(lights(), Neighborhood::Moore, life);
+        automaton.run(4);
+        assert_eq!(4, automaton.state.iter().filter(|&&on| on).count());
+
+        // corners stuck on
+        let stuck = |pos: Coord2, alive: &bool, neighbors: &[Option<&bool>]| {
+            (pos.x() % 5 == 0 && pos.y() % 5 == 0) || life(pos, alive, neighbors)
+        };
+        let mut initial = lights();
+        for corner in [0, 5, 30, 35] {
+            initial[corner] = true;
+        }
+        let mut automaton = Automaton::new(initial, Neighborhood::Moore, stuck);
+        automaton.run(5);
+        assert_eq!(17, automaton.state.iter().filter(|&&on| on).count());
+    }
+
+    #[test]
+    fn seating() {
+        let seats = Grid::new2d_map_str(
+            "L.LL.LL.LL\nLLLLLLL.LL\nL.L.L..L..\nLLLL.LL.LL\nL.LL.LL.LL\n\
+             L.LLLLL.LL\n..L.L.....\nLLLLLLLLLL\nL.LLLLLL.L\nL.LLLLL.LL",
+            |c| c,
+        )
+        .unwrap();
+        let rule = |tolerance: usize| {
+            move |_: Coord2, &seat: &char, neighbors: &[Option<&char>]| {
+                let occupied = neighbors.iter().flatten().filter(|&&&c| c == '#').count();
+                match seat {
+                    'L' if occupied == 0 => '#',
+                    '#' if occupied >= tolerance => 'L',
+                    _ => seat,
+                }
+            }
+        };
+
+        let mut adjacent = Automaton::new(seats.clone(), Neighborhood::Moore, rule(4));
+        adjacent.run_until_stable();
+        assert_eq!(37, adjacent.state.iter().filter(|&&c| c == '#').count());
+
+        let mut visible = Automaton::new(seats, Neighborhood::LineOfSight(|&c| c == '.'), rule(5));
+        visible.run_until_stable();
+        assert_eq!(26, visible.state.iter().filter(|&&c| c == '#').count());
+    }
+
+    #[test]
+    fn sparse_3d() {
+        let mut cubes = SparseGrid::new(false);
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            cubes.insert(Coord::new([x, y, 0]), true);
+        }
+        let rule = |_: Coord<i64, 3>, &active: &bool, neighbors: &[Option<&bool>]| {
+            let count = neighbors.iter().flatten().filter(|&&&on| on).count();
+            count == 3 || (active && count == 2)
+        };
+        let mut automaton = Automaton::new(cubes, Neighborhood::Moore, rule);
+        automaton.run(6);
+        assert_eq!(112, automaton.state.len());
+    }
+
+    #[test]
+    fn cycles() {
+        let blinker = Grid::new2d_map_str(".....\n..#..\n..#..\n..#..\n.....", |c| c == '#')
+            .unwrap();
+        let mut automaton = Automaton::new(blinker.clone(), Neighborhood::Moore, life);
+        let cycle = automaton.find_cycle(10);
+        assert_eq!(Some(Cycle { start: 0, length: 2 }), cycle);
+        assert_eq!(2, automaton.generation);
+        automaton.step();
+        assert_eq!(Some(Cycle { start: 3, length: 2 }), automaton.find_cycle(10));
+        assert_eq!(blinker, automaton.state_at(6));
+        assert_eq!((6, &blinker), (automaton.generation, &automaton.state));
+
+        let mut automaton = Automaton::new(blinker.clone(), Neighborhood::Moore, life);
+        assert_eq!(blinker, automaton.state_at(1_000_000_000));
+        assert_eq!(1_000_000_000, automaton.generation);
+        let flipped = automaton.state_at(1_000_000_001);
+        assert_ne!(blinker, flipped);
+        assert_eq!(flipped, automaton.state);
+
+        // a glider drifts away forever
+        let mut glider = SparseGrid::new(false);
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            glider.insert(Coord2::new2d(x, y), true);
+        }
+        let mut automaton = Automaton::new(glider, Neighborhood::Moore, life);
+        assert_eq!(None, automaton.find_cycle(20));
+        assert_eq!(20, automaton.generation);
+
+        // in-place updates see their own generation: a west-to-east sweep copies the first
+        // column all the way across in a single step
+        let copy_west = |_: Coord2, &on: &bool, neighbors: &[Option<&bool>]| {
+            neighbors[0].map_or(on, |&west| west)
+        };
+        let offsets = Neighborhood::Offsets(vec![Coord2::new2d(-1, 0)]);
+        let row = Grid::new2d(vec![true, false, false, false], 4).unwrap();
+        let mut sync = Automaton::new(row.clone(), offsets.clone(), copy_west);
+        sync.step();
+        assert_eq!(2, sync.state.iter().filter(|&&on| on).count());
+        let mut in_place = Automaton::new(row, offsets, copy_west).with_update(Update::InPlace);
+        in_place.step();
+        assert_eq!(4, in_place.state.iter().filter(|&&on| on).count());
+    }
+
+    #[test]
+    fn border_neighbors_keep_their_slots() {
+        // cells on the west edge take their east neighbor, every other cell its west neighbor
+        let west_or_east = |_: Coord2, &on: &bool, neighbors: &[Option<&bool>]| match *neighbors {
+            [None, Some(&east)] => east,
+            [Some(&west), _] => west,
+            _ => on,
+        };
+        let offsets = Neighborhood::Offsets(vec![Coord2::new2d(-1, 0), Coord2::new2d(1, 0)]);
+        let row = Grid::new2d(vec![true, false, false, true], 4).unwrap();
+        let mut automaton = Automaton::new(row, offsets, west_or_east);
+        automaton.step();
+        let expected = Grid::new2d(vec![false, true, false, false], 4).unwrap();
+        assert_eq!(expected, automaton.state);
+    }
+}
@@ -0,0 +1,286 @@
+use rustc_hash::{FxHashMap, FxHasher};
+use std::hash::{Hash, Hasher};
+
+/// States `start..start + length` repeat forever.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The earliest step whose state matches step `n`.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+/// A detected cycle along with every state up to the first repeat, so `states[i]` is the state
+/// after `i` steps.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CycleHistory<S> {
+    pub cycle: Cycle,
+    pub states: Vec<S>,
+}
+
+impl<S> CycleHistory<S> {
+    /// Number of steps before the cycle begins.
+    pub fn prefix(&self) -> usize {
+        self.cycle.start
+    }
+
+    /// The state after `n` steps, however large `n` is. For a history found by key, only the
+    /// key of the returned state is sure to match; use `extrapolate` for the rest.
+    pub fn nth_state(&self, n: usize) -> &S {
+        &self.states[self.cycle.reduce(n)]
+    }
+
+    /// Value after `n` steps of a quantity that grows by the same amount every lap of the cycle,
+    /// like the tower height in y22d17, even though the states only repeat under a key.
+    pub fn extrapolate<F>(&self, n: usize, value: F) -> i64
+    where
+        F: Fn(&S) -> i64,
+    {
+        let Cycle { start, length } = self.cycle;
+        if n < start {
+            return value(&self.states[n]);
+        }
+        let laps = ((n - start) / length) as i64;
+        let lap_gain = value(&self.states[start + length]) - value(&self.states[start]);
+        value(&self.states[self.cycle.reduce(n)]) + laps * lap_gain
+    }
+}
+
+/// Steps from `initial` until a state repeats. States are looked up by hash and compared in
+/// place, so each is stored only once, in the history.
+pub fn find_cycle<S, F>(initial: S, step: F) -> CycleHistory<S>
+where
+    S: Eq + Hash,
+    F: FnMut(&S) -> S,
+{
+    match record(initial, step, fingerprint, S::eq, usize::MAX) {
+        Ok(history) => history,
+        Err(_) => unreachable!("the step limit is never reached"),
+    }
+}
+
+/// Steps from `initial` until two states share a key, hashing only the keys. The key must
+/// capture everything that decides future keys, so that states with equal keys go on to
+/// repeat; anything else in the state, like a running total, may keep changing. The returned
+/// history holds one state past the cycle, the first one whose key repeats.
+pub fn find_cycle_by_key<S, K, F, G>(initial: S, step: F, key: G) -> CycleHistory<S>
+where
+    K: Eq + Hash,
+    F: FnMut(&S) -> S,
+    G: FnMut(&S) -> K,
+{
+    match record(initial, step, key, |_, _| true, usize::MAX) {
+        Ok(history) => history,
+        Err(_) => unreachable!("the step limit is never reached"),
+    }
+}
+
+/// The state after `n` steps from `initial`, skipping ahead once the states start to repeat.
+pub fn nth_state<S, F>(initial: S, step: F, n: usize) -> S
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> S,
+{
+    match record(initial, step, fingerprint, S::eq, n) {
+        Ok(history) => history.nth_state(n).clone(),
+        Err(state) => state,
+    }
+}
+
+/// The key of the state after `n` steps from `initial`, detecting repeats by key as in
+/// `find_cycle_by_key`. Only the key is returned, since the rest of the state may not repeat.
+pub fn nth_key<S, K, F, G>(initial: S, step: F, mut key: G, n: usize) -> K
+where
+    K: Eq + Hash,
+    F: FnMut(&S) -> S,
+    G: FnMut(&S) -> K,
+{
+    match record(initial, step, &mut key, |_, _| true, n) {
+        Ok(history) => key(history.nth_state(n)),
+        Err(state) => key(&state),
+    }
+}
+
+fn fingerprint<S: Hash>(state: &S) -> u64 {
+    let mut hasher = FxHasher::default();
+    state.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Records states until one repeats, or returns the state after `limit` steps if that comes
+/// first. States with equal keys are candidates, and `same` decides whether they match.
+pub(crate) fn record<S, K, F, G, E>(
+    initial: S,
+    mut step: F,
+    mut key: G,
+    same: E,
+    limit: usize,
+) -> Result<CycleHistory<S>, S>
+where
+    K: Eq + Hash,
+    F: FnMut(&S) -> S,
+    G: FnMut(&S) -> K,
+    E: Fn(&S, &S) -> bool,
+{
+    let mut seen: FxHashMap<K, Vec<usize>> = FxHashMap::default();
+    let mut states = vec![initial];
+    loop {
+        let index = states.len() - 1;
+        let current = &states[index];
+        let candidates = seen.entry(key(current)).or_default();
+        if let Some(&start) = candidates
+            .iter()
+            .find(|&&candidate| same(&states[candidate], current))
+        {
+            let cycle = Cycle {
+                start,
+                length: index - start,
+            };
+            return Ok(CycleHistory { cycle, states });
+        }
+        if index == limit {
+            return Err(states.pop().expect("there is always a current state"));
+        }
+        candidates.push(index);
+        let next = step(current);
+        states.push(next);
+    }
+}
+
+/// Floyd's tortoise and hare: finds the cycle storing only two states, at the cost of about
+/// three steps per state visited.
+pub fn floyd<S, F>(initial: S, mut step: F) -> Cycle
+where
+    S: PartialEq,
+    F: FnMut(&S) -> S,
+{
+    let mut tortoise = step(&initial);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        let ahead = step(&hare);
+        hare = step(&ahead);
+    }
+
+    let mut start = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+    Cycle { start, length }
+}
+
+/// Brent's algorithm: like `floyd`, but finds the length first and needs fewer steps.
+pub fn brent<S, F>(initial: S, mut step: F) -> Cycle
+where
+    S: Clone + PartialEq,
+    F: FnMut(&S) -> S,
+{
+    let (mut power, mut length) = (1, 1);
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    Cycle { start, length }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One y17d06 redistribution cycle.
+    fn redistribute(banks: &[usize]) -> Vec<usize> {
+        let mut banks = banks.to_vec();
+        let (index, &blocks) = banks
+            .iter()
+            .enumerate()
+            .rev()
+            .max_by_key(|&(_, blocks)| blocks)
+            .unwrap();
+        banks[index] = 0;
+        for offset in 1..=blocks {
+            let len = banks.len();
+            banks[(index + offset) % len] += 1;
+        }
+        banks
+    }
+
+    #[test]
+    fn memory_banks() {
+        let expected = Cycle {
+            start: 1,
+            length: 4,
+        };
+        let redistribute = |banks: &Vec<usize>| redistribute(banks);
+        let history = find_cycle(vec![0, 2, 7, 0], redistribute);
+        assert_eq!(expected, history.cycle);
+        assert_eq!(5, history.prefix() + history.cycle.length);
+        assert_eq!(vec![2, 4, 1, 2], history.states[1]);
+        assert_eq!(expected, floyd(vec![0, 2, 7, 0], redistribute));
+        assert_eq!(expected, brent(vec![0, 2, 7, 0], redistribute));
+
+        assert_eq!(&vec![3, 1, 2, 3], history.nth_state(1_000_000_002));
+        assert_eq!(
+            vec![3, 1, 2, 3],
+            nth_state(vec![0, 2, 7, 0], redistribute, 1_000_000_002)
+        );
+        assert_eq!(
+            vec![0, 2, 7, 0],
+            nth_state(vec![0, 2, 7, 0], redistribute, 0)
+        );
+    }
+
+    #[test]
+    fn keyed() {
+        // a running total alongside a repeating value, as with tower heights in y22d17
+        let step = |&(value, total): &(u64, i64)| {
+            let next = (value * value + 1) % 255;
+            (next, total + next as i64)
+        };
+        let brute = |n: usize| (0..n).fold((3, 0), |state, _| step(&state));
+
+        let history = find_cycle_by_key((3, 0), step, |&(value, _)| value);
+        for n in [0, 5, 40, 1_000, 12_345] {
+            let value = nth_key((3, 0), step, |&(value, _)| value, n);
+            let total = history.extrapolate(n, |&(_, total)| total);
+            assert_eq!(brute(n), (value, total));
+        }
+    }
+}
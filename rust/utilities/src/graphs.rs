@@ -1,10 +1,16 @@
+pub mod cliques;
+pub mod longest_path;
+pub mod strategies;
+pub mod tsp;
+pub mod visited;
+
 use crate::structs::store::Store;
 use num_traits::{One, Zero};
-use rustc_hash::FxHashMap;
 use std::cmp::Reverse;
 use std::collections::BinaryHeap;
 use std::fmt::Debug;
 use std::{collections::VecDeque, hash::Hash};
+pub use visited::{BitVisited, DenseVisited, SearchStats, Traversal, Visited, WeightedStore};
 
 pub fn bfs<S, C, FN, FS>(start: S, edges: FN, end_condition: FS) -> PathInfo<S, C>
 where
@@ -12,9 +18,25 @@ where
     C: Debug + Zero + One + Copy + Hash + Eq,
     FN: Fn(EdgeInfo<C>, &S) -> Vec<S>,
     FS: Fn(EdgeInfo<C>, &S) -> bool,
+{
+    bfs_with(start, edges, end_condition, Store::new()).into()
+}
+
+pub fn bfs_with<S, C, FN, FS, V>(
+    start: S,
+    edges: FN,
+    end_condition: FS,
+    visited: V,
+) -> Traversal<V>
+where
+    S: Debug + Eq + Hash + Clone,
+    C: Debug + Zero + One + Copy + Hash + Eq,
+    FN: Fn(EdgeInfo<C>, &S) -> Vec<S>,
+    FS: Fn(EdgeInfo<C>, &S) -> bool,
+    V: Visited<S, C>,
 {
     let frontier: VecDeque<(EdgeInfo<C>, S)> = VecDeque::new();
-    traverse_unweighted(start, edges, end_condition, frontier)
+    traverse_unweighted(start, edges, end_condition, frontier, visited)
 }
 
 pub fn dfs<S, C, FN, FS>(start: S, edges: FN, end_condition: FS) -> PathInfo<S, C>
@@ -23,24 +45,42 @@ where
     C: Debug + Zero + One + Copy + Hash + Eq,
     FN: Fn(EdgeInfo<C>, &S) -> Vec<S>,
     FS: Fn(EdgeInfo<C>, &S) -> bool,
+{
+    dfs_with(start, edges, end_condition, Store::new()).into()
+}
+
+pub fn dfs_with<S, C, FN, FS, V>(
+    start: S,
+    edges: FN,
+    end_condition: FS,
+    visited: V,
+) -> Traversal<V>
+where
+    S: Debug + Eq + Hash + Clone,
+    C: Debug + Zero + One + Copy + Hash + Eq,
+    FN: Fn(EdgeInfo<C>, &S) -> Vec<S>,
+    FS: Fn(EdgeInfo<C>, &S) -> bool,
+    V: Visited<S, C>,
 {
     let frontier: Vec<(EdgeInfo<C>, S)> = Vec::new();
 
-    traverse_unweighted(start, edges, end_condition, frontier)
+    traverse_unweighted(start, edges, end_condition, frontier, visited)
 }
 
-fn traverse_unweighted<'a, S, C, FN, FS, FR>(
+fn traverse_unweighted<'a, S, C, FN, FS, FR, V>(
     start: S,
     edges: FN,
     end_condition: FS,
     mut frontier: FR,
-) -> PathInfo<S, C>
+    mut visited: V,
+) -> Traversal<V>
 where
     S: Debug + Eq + Hash + Clone + 'a,
     C: Debug + Zero + One + Copy + Hash + Eq + 'a,
     FN: Fn(EdgeInfo<C>, &S) -> Vec<S>,
     FS: Fn(EdgeInfo<C>, &S) -> bool,
     FR: Frontier<(EdgeInfo<C>, S)>,
+    V: Visited<S, C>,
 {
     frontier.push_node((
         EdgeInfo {
@@ -49,23 +89,29 @@ where
         },
         start,
     ));
-    let mut visited = Store::new();
+    let mut stats = SearchStats {
+        peak_frontier: 1,
+        ..Default::default()
+    };
 
     while let Some((info, node)) = frontier.pop_node() {
         // add to visited
-        let Some(id) = visited.assign(node.clone(), info) else {
+        let Some(id) = visited.visit(&node, info) else {
+            stats.duplicates += 1;
             continue;
         };
 
         // check end condition and exit if successful
         if end_condition(info, &node) {
-            return PathInfo {
-                nodes: visited,
+            return Traversal {
+                visited,
                 end_index: Some(id),
+                stats,
             };
         }
 
         // add connected nodes to frontier
+        stats.expanded += 1;
         let cost = info.cost + C::one();
         for edge in edges(info, &node).into_iter() {
             frontier.push_node((
@@ -76,10 +122,12 @@ where
                 edge,
             ));
         }
+        stats.peak_frontier = stats.peak_frontier.max(frontier.size());
     }
-    PathInfo {
-        nodes: visited,
+    Traversal {
+        visited,
         end_index: None,
+        stats,
     }
 }
 
@@ -93,26 +141,42 @@ where
     C: Debug + Zero + One + Copy + Hash + Eq + Ord,
     FN: Fn(EdgeInfo<C>, &S) -> Vec<(S, C)>,
     FS: Fn(EdgeInfo<C>, &S) -> bool,
+{
+    dijkstra_with(start, edges, end_condition, WeightedStore::new()).into()
+}
+
+pub fn dijkstra_with<S, C, FN, FS, V>(
+    start: S,
+    edges: FN,
+    end_condition: FS,
+    visited: V,
+) -> Traversal<V>
+where
+    S: Debug + Eq + Hash + Ord + Clone,
+    C: Debug + Zero + One + Copy + Hash + Eq + Ord,
+    FN: Fn(EdgeInfo<C>, &S) -> Vec<(S, C)>,
+    FS: Fn(EdgeInfo<C>, &S) -> bool,
+    V: Visited<S, C>,
 {
     let frontier: BinaryHeap<Reverse<(EdgeInfo<C>, S)>> = BinaryHeap::new();
-    traverse_weighted(start, edges, end_condition, frontier)
+    traverse_weighted(start, edges, end_condition, frontier, visited)
 }
 
-fn traverse_weighted<S, C, FN, FS>(
+fn traverse_weighted<S, C, FN, FS, V>(
     start: S,
     edges: FN,
     end_condition: FS,
-    mut frontier: BinaryHeap<Reverse<(EdgeInfo<C>, S)>>
-) -> PathInfo<S, C>
+    mut frontier: BinaryHeap<Reverse<(EdgeInfo<C>, S)>>,
+    mut visited: V,
+) -> Traversal<V>
 where
     C: Copy + Debug + Eq + Hash + One + Ord + Zero,
     FN: Fn(EdgeInfo<C>, &S) -> Vec<(S, C)>,
     FS: Fn(EdgeInfo<C>, &S) -> bool,
     S: Clone + Debug + Eq + Hash + Ord,
+    V: Visited<S, C>,
 {
-    let mut costs = FxHashMap::default();
-    costs.insert(start.clone(), C::zero());
-
+    visited.improve(&start, C::zero());
     frontier.push_node(Reverse((
         EdgeInfo {
             cost: C::zero(),
@@ -120,26 +184,30 @@ where
         },
         start
     )));
-    let mut visited = Store::new();
+    let mut stats = SearchStats {
+        peak_frontier: 1,
+        ..Default::default()
+    };
     while let Some(Reverse((info, node))) = frontier.pop_node() {
-        let Some(id) = visited.assign(node.clone(), info) else {
+        let Some(id) = visited.visit(&node, info) else {
+            stats.duplicates += 1;
             continue;
         };
 
         // check end condition and exit if successful
         if end_condition(info, &node) {
-            return PathInfo {
-                nodes: visited,
+            return Traversal {
+                visited,
                 end_index: Some(id),
+                stats,
             };
         }
 
         // add connected nodes to frontier
+        stats.expanded += 1;
         for (edge_state, edge_cost) in edges(info, &node).into_iter() {
             let alternate_cost = info.cost + edge_cost;
-            let cost = *costs.get(&edge_state).unwrap_or(&(alternate_cost + C::one()));
-            if alternate_cost < cost {
-                costs.insert(edge_state.clone(), alternate_cost);
+            if visited.improve(&edge_state, alternate_cost) {
                 frontier.push_node(Reverse((
                     EdgeInfo {
                         cost: alternate_cost,
@@ -149,10 +217,12 @@ where
                 )));
             }
         }
+        stats.peak_frontier = stats.peak_frontier.max(frontier.size());
     }
-    PathInfo {
-        nodes: visited,
+    Traversal {
+        visited,
         end_index: None,
+        stats,
     }
 }
 
@@ -243,6 +313,7 @@ where
 trait Frontier<T> {
     fn push_node(&mut self, value: T);
     fn pop_node(&mut self) -> Option<T>;
+    fn size(&self) -> usize;
 }
 
 impl<T> Frontier<T> for Vec<T> {
@@ -253,6 +324,10 @@ impl<T> Frontier<T> for Vec<T> {
     fn pop_node(&mut self) -> Option<T> {
         self.pop()
     }
+
+    fn size(&self) -> usize {
+        self.len()
+    }
 }
 
 impl<T> Frontier<T> for VecDeque<T> {
@@ -263,6 +338,10 @@ impl<T> Frontier<T> for VecDeque<T> {
     fn pop_node(&mut self) -> Option<T> {
         self.pop_front()
     }
+
+    fn size(&self) -> usize {
+        self.len()
+    }
 }
 
 impl<T> Frontier<T> for BinaryHeap<T>
@@ -271,6 +350,7 @@ where
 {
     fn push_node(&mut self, value: T) { self.push(value); }
     fn pop_node(&mut self) -> Option<T> { self.pop() }
+    fn size(&self) -> usize { self.len() }
 }
 
 #[test]
@@ -325,3 +405,106 @@ fn dfs_test() {
         vec![1, 2, 4, 16, 17]
     );
 }
+
+#[test]
+fn visited_backends_test() {
+    // 10x10 open grid, walking from the top left to the bottom right
+    let width = 10;
+    let edges = |_: EdgeInfo<usize>, &pos: &usize| {
+        let mut next = Vec::new();
+        if pos % width > 0 {
+            next.push(pos - 1);
+        }
+        if pos % width < width - 1 {
+            next.push(pos + 1);
+        }
+        if pos >= width {
+            next.push(pos - width);
+        }
+        if pos + width < width * width {
+            next.push(pos + width);
+        }
+        next
+    };
+    let end = |_: EdgeInfo<usize>, &pos: &usize| pos == width * width - 1;
+
+    let stored = bfs_with(0, edges, end, Store::new());
+    let dense = bfs_with(0, edges, end, DenseVisited::new(width * width, |&pos: &usize| pos));
+    let end_slot = dense.end_index.unwrap();
+    assert_eq!(width * width - 1, end_slot);
+    assert_eq!(Some(18), dense.visited.get(end_slot).map(|info| info.cost));
+    assert_eq!(19, dense.visited.path(end_slot).len());
+    assert_eq!(stored.stats, dense.stats);
+    assert_eq!(Some(18), PathInfo::from(stored).steps());
+
+    let bits = BitVisited::new(width * width, |&pos: &usize| pos);
+    let reachable = dfs_with(0, edges, no_end_condition, bits);
+    assert_eq!(width * width, reachable.visited.reached.len());
+    assert_eq!(width * width, reachable.stats.expanded);
+    assert!(reachable.stats.duplicates > 0);
+    assert!(reachable.stats.peak_frontier > 1);
+}
+
+#[test]
+fn dense_visited_never_hashes() {
+    use std::hash::Hasher;
+
+    // grid cells that refuse to be hashed or cloned
+    #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+    struct Cell(usize);
+
+    impl Hash for Cell {
+        fn hash<H: Hasher>(&self, _: &mut H) {
+            panic!("dense traversals should not hash states");
+        }
+    }
+
+    impl Clone for Cell {
+        fn clone(&self) -> Self {
+            panic!("dense traversals should not clone states");
+        }
+    }
+
+    let width = 10;
+    let moves = |pos: usize| {
+        let mut next = Vec::new();
+        if pos % width < width - 1 {
+            next.push((pos + 1, 1 + pos % 3));
+        }
+        if pos + width < width * width {
+            next.push((pos + width, 2));
+        }
+        if pos >= width {
+            next.push((pos - width, 1));
+        }
+        next
+    };
+    let expected = dijkstra(0, |_, &pos| moves(pos), |_, &pos| pos == width * width - 1);
+    let stored = dijkstra_with(
+        0,
+        |_, &pos| moves(pos),
+        |_, &pos| pos == width * width - 1,
+        WeightedStore::new(),
+    );
+
+    let edges = |_: EdgeInfo<usize>, cell: &Cell| {
+        moves(cell.0).into_iter().map(|(pos, cost)| (Cell(pos), cost)).collect()
+    };
+    let end = |_: EdgeInfo<usize>, cell: &Cell| cell.0 == width * width - 1;
+    let dense = dijkstra_with(
+        Cell(0),
+        edges,
+        end,
+        DenseVisited::new(width * width, |cell: &Cell| cell.0),
+    );
+    let end_slot = dense.end_index.unwrap();
+    assert_eq!(expected.steps(), dense.visited.get(end_slot).map(|info| info.cost));
+    let path: Vec<usize> = expected
+        .path(expected.end_index.unwrap())
+        .into_iter()
+        .map(|step| step.state)
+        .collect();
+    assert_eq!(path, dense.visited.path(end_slot));
+    // both backends only queue improvements, so they search identically
+    assert_eq!(stored.stats, dense.stats);
+}
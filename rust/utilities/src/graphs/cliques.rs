@@ -0,0 +1,255 @@
+use bit_set::BitSet;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Builds an undirected adjacency list of `BitSet`s from a list of edges between node indices.
+/// Self-loops are ignored, since they have no meaning for cliques.
+pub fn adjacency_from_edges<I>(nodes: usize, edges: I) -> Vec<BitSet>
+where
+    I: IntoIterator<Item = (usize, usize)>,
+{
+    let mut adjacency = vec![BitSet::with_capacity(nodes); nodes];
+    for (a, b) in edges {
+        if a != b {
+            adjacency[a].insert(b);
+            adjacency[b].insert(a);
+        }
+    }
+    adjacency
+}
+
+/// Enumerates every maximal clique using Bron–Kerbosch with pivoting, seeded in degeneracy
+/// order so that large sparse graphs stay tractable.
+pub fn maximal_cliques(adjacency: &[BitSet]) -> Vec<BitSet> {
+    let mut collector = CollectAll(Vec::new());
+    enumerate(adjacency, &mut collector);
+    collector.0
+}
+
+/// Finds a clique of maximum size. Ties are broken by whichever clique is found first.
+pub fn maximum_clique(adjacency: &[BitSet]) -> BitSet {
+    let mut collector = KeepLargest(BitSet::new());
+    enumerate(adjacency, &mut collector);
+    collector.0
+}
+
+/// Enumerates every clique of exactly `k` nodes. Each clique is listed once, with its nodes
+/// in ascending order.
+pub fn k_cliques(adjacency: &[BitSet], k: usize) -> Vec<Vec<usize>> {
+    let mut cliques = Vec::new();
+    if k == 0 {
+        return cliques;
+    }
+    let candidates: BitSet = (0..adjacency.len()).collect();
+    let mut clique = Vec::with_capacity(k);
+    extend_k_clique(adjacency, k, &mut clique, &candidates, &mut cliques);
+    cliques
+}
+
+fn extend_k_clique(
+    adjacency: &[BitSet],
+    k: usize,
+    clique: &mut Vec<usize>,
+    candidates: &BitSet,
+    cliques: &mut Vec<Vec<usize>>,
+) {
+    if clique.len() == k {
+        cliques.push(clique.clone());
+        return;
+    }
+    for node in candidates.iter() {
+        // only look at higher-numbered nodes so that each clique is generated once
+        let next: BitSet = candidates
+            .intersection(&adjacency[node])
+            .filter(|&other| other > node)
+            .collect();
+        if clique.len() + 1 + next.len() < k {
+            continue;
+        }
+        clique.push(node);
+        extend_k_clique(adjacency, k, clique, &next, cliques);
+        clique.pop();
+    }
+}
+
+trait CliqueVisitor {
+    fn prune(&self, reachable: usize) -> bool;
+    fn visit(&mut self, clique: &BitSet);
+}
+
+struct CollectAll(Vec<BitSet>);
+
+impl CliqueVisitor for CollectAll {
+    fn prune(&self, _: usize) -> bool {
+        false
+    }
+
+    fn visit(&mut self, clique: &BitSet) {
+        self.0.push(clique.clone());
+    }
+}
+
+struct KeepLargest(BitSet);
+
+impl CliqueVisitor for KeepLargest {
+    fn prune(&self, reachable: usize) -> bool {
+        reachable <= self.0.len()
+    }
+
+    fn visit(&mut self, clique: &BitSet) {
+        if clique.len() > self.0.len() {
+            self.0 = clique.clone();
+        }
+    }
+}
+
+fn enumerate<V: CliqueVisitor>(adjacency: &[BitSet], visitor: &mut V) {
+    let order = degeneracy_order(adjacency);
+    let mut position = vec![0; adjacency.len()];
+    for (index, &node) in order.iter().enumerate() {
+        position[node] = index;
+    }
+
+    let mut clique = BitSet::with_capacity(adjacency.len());
+    for (index, &node) in order.iter().enumerate() {
+        let (later, earlier): (Vec<usize>, Vec<usize>) = adjacency[node]
+            .iter()
+            .partition(|&neighbor| position[neighbor] > index);
+        clique.insert(node);
+        bron_kerbosch(
+            adjacency,
+            &mut clique,
+            later.into_iter().collect(),
+            earlier.into_iter().collect(),
+            visitor,
+        );
+        clique.remove(node);
+    }
+}
+
+fn bron_kerbosch<V: CliqueVisitor>(
+    adjacency: &[BitSet],
+    clique: &mut BitSet,
+    mut candidates: BitSet,
+    mut excluded: BitSet,
+    visitor: &mut V,
+) {
+    if candidates.is_empty() {
+        if excluded.is_empty() {
+            visitor.visit(clique);
+        }
+        return;
+    }
+    if visitor.prune(clique.len() + candidates.len()) {
+        return;
+    }
+
+    // the pivot covers as many candidates as possible, so only its non-neighbors need branching
+    let pivot = candidates
+        .union(&excluded)
+        .max_by_key(|&node| adjacency[node].intersection(&candidates).count())
+        .expect("candidates is not empty");
+    let branches: Vec<usize> = candidates.difference(&adjacency[pivot]).collect();
+
+    for node in branches {
+        let mut next_candidates = candidates.clone();
+        next_candidates.intersect_with(&adjacency[node]);
+        let mut next_excluded = excluded.clone();
+        next_excluded.intersect_with(&adjacency[node]);
+
+        clique.insert(node);
+        bron_kerbosch(adjacency, clique, next_candidates, next_excluded, visitor);
+        clique.remove(node);
+
+        candidates.remove(node);
+        excluded.insert(node);
+    }
+}
+
+/// Orders nodes by repeatedly removing the node of smallest remaining degree.
+fn degeneracy_order(adjacency: &[BitSet]) -> Vec<usize> {
+    let mut degrees: Vec<usize> = adjacency.iter().map(|neighbors| neighbors.len()).collect();
+    let mut removed = vec![false; adjacency.len()];
+    let mut heap: BinaryHeap<Reverse<(usize, usize)>> = degrees
+        .iter()
+        .enumerate()
+        .map(|(node, &degree)| Reverse((degree, node)))
+        .collect();
+
+    let mut order = Vec::with_capacity(adjacency.len());
+    while let Some(Reverse((degree, node))) = heap.pop() {
+        if removed[node] || degree != degrees[node] {
+            continue;
+        }
+        removed[node] = true;
+        order.push(node);
+        for neighbor in adjacency[node].iter() {
+            if !removed[neighbor] {
+                degrees[neighbor] -= 1;
+                heap.push(Reverse((degrees[neighbor], neighbor)));
+            }
+        }
+    }
+    order
+}
+
+#[cfg(test)]
+fn lan_party() -> (crate::structs::indexer::Indexer<&'static str>, Vec<BitSet>) {
+    use crate::structs::indexer::Indexer;
+
+    let input = "kh-tc qp-kh de-cg ka-co yn-aq qp-ub cg-tb vc-aq tb-ka wh-tc yn-cg kh-ub ta-co de-co \
+        tc-td tb-wq wh-td ta-ka td-qp aq-cg wq-ub ub-vc de-ta wq-aq wq-vc wh-yn ka-de kh-ta co-tc \
+        wh-qp tb-vc td-yn";
+    let mut indexer = Indexer::new();
+    let edges: Vec<_> = input
+        .split_whitespace()
+        .map(|edge| {
+            let (a, b) = edge.split_once('-').unwrap();
+            (indexer.get_or_assign_index(a), indexer.get_or_assign_index(b))
+        })
+        .collect();
+    let adjacency = adjacency_from_edges(indexer.len(), edges);
+    (indexer, adjacency)
+}
+
+#[test]
+fn maximal_cliques_test() {
+    let adjacency = adjacency_from_edges(6, [(0, 1), (1, 2), (0, 2), (2, 3), (3, 4)]);
+    let mut cliques: Vec<Vec<usize>> = maximal_cliques(&adjacency)
+        .into_iter()
+        .map(|clique| clique.iter().collect())
+        .collect();
+    cliques.sort();
+    assert_eq!(vec![vec![0, 1, 2], vec![2, 3], vec![3, 4], vec![5]], cliques);
+}
+
+#[test]
+fn maximum_clique_test() {
+    use itertools::Itertools;
+
+    let (indexer, adjacency) = lan_party();
+    let password = maximum_clique(&adjacency)
+        .iter()
+        .map(|node| *indexer.get_value(node).unwrap())
+        .sorted_unstable()
+        .join(",");
+    assert_eq!("co,de,ka,ta", password);
+}
+
+#[test]
+fn k_cliques_test() {
+    let (indexer, adjacency) = lan_party();
+    let triangles = k_cliques(&adjacency, 3);
+    assert_eq!(12, triangles.len());
+    let with_t = triangles
+        .iter()
+        .filter(|triangle| {
+            triangle
+                .iter()
+                .any(|&node| indexer.get_value(node).unwrap().starts_with('t'))
+        })
+        .count();
+    assert_eq!(7, with_t);
+    assert_eq!(adjacency.len(), k_cliques(&adjacency, 1).len());
+    assert!(k_cliques(&adjacency, 5).is_empty());
+}
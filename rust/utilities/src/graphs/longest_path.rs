@@ -0,0 +1,337 @@
+use num_traits::{ToPrimitive, Zero};
+use rayon::prelude::*;
+use rustc_hash::FxHashMap;
+use std::ops::{Add, Sub};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Largest graph for which memoization on (node, visited) is used by default. Beyond this the
+/// number of distinct visited masks tends to outgrow the benefit of caching them. The memo
+/// holds at most one entry per reachable (node, visited) pair, so its worst case is
+/// `nodes * 2^nodes` entries: around 400 million, several gigabytes, at this limit. Real graphs
+/// reach only a small fraction of those masks.
+pub const MEMOIZE_LIMIT: usize = 24;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LongestPathConfig {
+    /// Cache the best remaining length for each (node, visited) pair. Exact, but disables
+    /// upper-bound pruning, and the cache is unbounded; see `MEMOIZE_LIMIT` for its size.
+    pub memoize: bool,
+    /// Number of edges to expand serially before handing each partial path to a rayon task.
+    /// Zero searches everything on the current thread.
+    pub parallel_depth: usize,
+}
+
+impl LongestPathConfig {
+    pub fn for_graph(nodes: usize) -> Self {
+        Self {
+            memoize: nodes <= MEMOIZE_LIMIT,
+            parallel_depth: 4,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LongestPath<C> {
+    pub length: C,
+    pub path: Vec<usize>,
+}
+
+/// Finds the longest path from `start` to `end` that visits no node twice. `edges[a]` lists
+/// `(b, weight)` for every edge leaving `a`; undirected graphs list each edge in both directions.
+/// Graphs are limited to 64 nodes so that the visited set fits in a `u64`. Parallel branches
+/// share the best length found so far, which requires lengths that convert to `u64`; any that
+/// do not are still searched, just without sharing their bound.
+pub fn longest_simple_path<C>(
+    edges: &[Vec<(usize, C)>],
+    start: usize,
+    end: usize,
+) -> Option<LongestPath<C>>
+where
+    C: Copy + Ord + Zero + Add<Output = C> + Sub<Output = C> + ToPrimitive + Send + Sync,
+{
+    longest_simple_path_with(edges, start, end, LongestPathConfig::for_graph(edges.len()))
+}
+
+pub fn longest_simple_path_with<C>(
+    edges: &[Vec<(usize, C)>],
+    start: usize,
+    end: usize,
+    config: LongestPathConfig,
+) -> Option<LongestPath<C>>
+where
+    C: Copy + Ord + Zero + Add<Output = C> + Sub<Output = C> + ToPrimitive + Send + Sync,
+{
+    assert!(edges.len() <= 64, "Visited masks are limited to 64 nodes.");
+
+    // the heaviest edge into each node bounds what visiting it can still add
+    let mut max_in = vec![C::zero(); edges.len()];
+    for &(to, weight) in edges.iter().flatten() {
+        max_in[to] = max_in[to].max(weight);
+    }
+    let remaining = max_in
+        .iter()
+        .enumerate()
+        .filter(|&(node, _)| node != start)
+        .fold(C::zero(), |acc, (_, &weight)| acc + weight);
+
+    let start_branch = Branch {
+        node: start,
+        visited: 1 << start,
+        length: C::zero(),
+        remaining,
+        path: vec![start],
+    };
+    let mut branches = vec![start_branch];
+    let mut finished = Vec::new();
+    for _ in 0..config.parallel_depth {
+        let mut next_branches = Vec::new();
+        for branch in branches {
+            if branch.node == end {
+                finished.push(branch);
+                continue;
+            }
+            for &(next, weight) in edges[branch.node].iter() {
+                if branch.visited & (1 << next) != 0 {
+                    continue;
+                }
+                let mut path = branch.path.clone();
+                path.push(next);
+                next_branches.push(Branch {
+                    node: next,
+                    visited: branch.visited | (1 << next),
+                    length: branch.length + weight,
+                    remaining: branch.remaining - max_in[next],
+                    path,
+                });
+            }
+        }
+        branches = next_branches;
+    }
+
+    let incumbent = Incumbent::default();
+    for branch in finished.iter() {
+        incumbent.offer(branch.length);
+    }
+    let searched = branches
+        .into_par_iter()
+        .filter_map(|branch| {
+            let search = |path| Search {
+                edges,
+                end,
+                max_in: &max_in,
+                memo: FxHashMap::default(),
+                path,
+                best: None,
+                incumbent: &incumbent,
+            };
+            if config.memoize {
+                // the memoized search rebuilds paths from its memo, so it needs no running path
+                search(Vec::new()).memoized(branch)
+            } else {
+                let mut search = search(branch.path);
+                search.pruned(branch.node, branch.visited, branch.length, branch.remaining);
+                search.best
+            }
+        })
+        .max_by_key(|longest| longest.length);
+
+    finished
+        .into_iter()
+        .map(|branch| LongestPath {
+            length: branch.length,
+            path: branch.path,
+        })
+        .chain(searched)
+        .max_by_key(|longest| longest.length)
+}
+
+struct Branch<C> {
+    node: usize,
+    visited: u64,
+    length: C,
+    remaining: C,
+    path: Vec<usize>,
+}
+
+/// The longest length any branch has reached so far, shared between rayon tasks so that each
+/// prunes against the best overall rather than only its own. Stored plus one, so zero means
+/// nothing has been found.
+#[derive(Default)]
+struct Incumbent(AtomicU64);
+
+impl Incumbent {
+    fn offer<C: ToPrimitive>(&self, length: C) {
+        if let Some(stored) = length.to_u64().and_then(|length| length.checked_add(1)) {
+            self.0.fetch_max(stored, Ordering::Relaxed);
+        }
+    }
+
+    /// Whether a branch that can reach at most `bound` cannot beat the best found anywhere.
+    fn rules_out<C: ToPrimitive>(&self, bound: C) -> bool {
+        let stored = self.0.load(Ordering::Relaxed);
+        stored != 0 && bound.to_u64().is_some_and(|bound| bound < stored)
+    }
+}
+
+struct Search<'a, C> {
+    edges: &'a [Vec<(usize, C)>],
+    end: usize,
+    max_in: &'a [C],
+    memo: FxHashMap<(usize, u64), Option<(C, usize)>>,
+    path: Vec<usize>,
+    best: Option<LongestPath<C>>,
+    incumbent: &'a Incumbent,
+}
+
+impl<C> Search<'_, C>
+where
+    C: Copy + Ord + Zero + Add<Output = C> + Sub<Output = C> + ToPrimitive,
+{
+    fn pruned(&mut self, node: usize, visited: u64, length: C, remaining: C) {
+        if node == self.end {
+            if self.best.as_ref().is_none_or(|best| length > best.length) {
+                self.best = Some(LongestPath {
+                    length,
+                    path: self.path.clone(),
+                });
+                self.incumbent.offer(length);
+            }
+            return;
+        }
+        // ties are pruned too, since whichever branch found that length keeps its path
+        let bound = length + remaining;
+        if self.best.as_ref().is_some_and(|best| bound <= best.length)
+            || self.incumbent.rules_out(bound)
+        {
+            return;
+        }
+        for &(next, weight) in self.edges[node].iter() {
+            if visited & (1 << next) != 0 {
+                continue;
+            }
+            self.path.push(next);
+            self.pruned(
+                next,
+                visited | (1 << next),
+                length + weight,
+                remaining - self.max_in[next],
+            );
+            self.path.pop();
+        }
+    }
+
+    fn memoized(&mut self, branch: Branch<C>) -> Option<LongestPath<C>> {
+        let (rest, _) = self.best_from(branch.node, branch.visited)?;
+        let mut path = branch.path;
+        let mut node = branch.node;
+        let mut visited = branch.visited;
+        while node != self.end {
+            let (_, next) = self.memo[&(node, visited)].expect("path was found");
+            node = next;
+            visited |= 1 << next;
+            path.push(next);
+        }
+        Some(LongestPath {
+            length: branch.length + rest,
+            path,
+        })
+    }
+
+    /// Longest distance from `node` to the end without revisiting, and the next hop to take.
+    fn best_from(&mut self, node: usize, visited: u64) -> Option<(C, usize)> {
+        if node == self.end {
+            return Some((C::zero(), node));
+        }
+        if let Some(&known) = self.memo.get(&(node, visited)) {
+            return known;
+        }
+        let mut best: Option<(C, usize)> = None;
+        for &(next, weight) in self.edges[node].iter() {
+            if visited & (1 << next) != 0 {
+                continue;
+            }
+            if let Some((rest, _)) = self.best_from(next, visited | (1 << next)) {
+                let length = weight + rest;
+                if best.is_none_or(|(best_length, _)| length > best_length) {
+                    best = Some((length, next));
+                }
+            }
+        }
+        self.memo.insert((node, visited), best);
+        best
+    }
+}
+
+#[cfg(test)]
+fn grid_graph(side: usize) -> Vec<Vec<(usize, u32)>> {
+    let weight = |a: usize, b: usize| ((a * 7 + b * 13) % 10 + 1) as u32;
+    (0..side * side)
+        .map(|node| {
+            let (x, y) = (node % side, node / side);
+            let mut neighbors = Vec::new();
+            if x > 0 {
+                neighbors.push(node - 1);
+            }
+            if x + 1 < side {
+                neighbors.push(node + 1);
+            }
+            if y > 0 {
+                neighbors.push(node - side);
+            }
+            if y + 1 < side {
+                neighbors.push(node + side);
+            }
+            neighbors
+                .into_iter()
+                .map(|other| (other, weight(node.min(other), node.max(other))))
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn longest_path_small() {
+    // 0 -> 1 -> 3 is short; 0 -> 2 -> 1 -> 3 is long
+    let edges: Vec<Vec<(usize, u32)>> = vec![
+        vec![(1, 1), (2, 5)],
+        vec![(0, 1), (2, 5), (3, 1)],
+        vec![(0, 5), (1, 5)],
+        vec![(1, 1)],
+    ];
+    let longest = longest_simple_path(&edges, 0, 3).unwrap();
+    assert_eq!(11, longest.length);
+    assert_eq!(vec![0, 2, 1, 3], longest.path);
+}
+
+#[test]
+fn longest_path_strategies_agree() {
+    let edges = grid_graph(5);
+    let end = edges.len() - 1;
+    let results: Vec<_> = [(false, 0), (false, 3), (true, 0), (true, 2)]
+        .into_iter()
+        .map(|(memoize, parallel_depth)| {
+            let config = LongestPathConfig {
+                memoize,
+                parallel_depth,
+            };
+            longest_simple_path_with(&edges, 0, end, config).unwrap()
+        })
+        .collect();
+    for longest in results.iter() {
+        assert_eq!(results[0].length, longest.length);
+        assert_eq!(Some(&0), longest.path.first());
+        assert_eq!(Some(&end), longest.path.last());
+        let length: u32 = longest
+            .path
+            .windows(2)
+            .map(|pair| edges[pair[0]].iter().find(|&&(to, _)| to == pair[1]).unwrap().1)
+            .sum();
+        assert_eq!(longest.length, length);
+    }
+}
+
+#[test]
+fn longest_path_unreachable() {
+    let edges: Vec<Vec<(usize, u32)>> = vec![vec![(1, 1)], vec![(0, 1)], vec![]];
+    assert_eq!(None, longest_simple_path(&edges, 0, 2));
+}
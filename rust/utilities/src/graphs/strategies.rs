@@ -0,0 +1,470 @@
+use crate::structs::store::Store;
+use num_traits::{One, Zero};
+use std::fmt::Debug;
+use std::hash::Hash;
+
+use super::{EdgeInfo, PathInfo};
+
+/// Breadth-first search from both ends at once, always expanding whichever side has the smaller
+/// frontier. `reverse_edges` must return the predecessors of a state. Each half numbers its own
+/// states, so the `parent` ids passed to `edges` and `reverse_edges` refer to separate searches.
+/// Because of this, the returned `PathInfo` only holds the nodes along the shortest path.
+pub fn bidirectional_bfs<S, C, FN, FR>(
+    start: S,
+    goal: S,
+    edges: FN,
+    reverse_edges: FR,
+) -> PathInfo<S, C>
+where
+    S: Debug + Eq + Hash + Clone,
+    C: Debug + Zero + One + Copy + Hash + Eq + Ord,
+    FN: Fn(EdgeInfo<C>, &S) -> Vec<S>,
+    FR: Fn(EdgeInfo<C>, &S) -> Vec<S>,
+{
+    if start == goal {
+        return path_info_from(vec![(start, C::zero())]);
+    }
+
+    let mut forward = Side::new(start);
+    let mut backward = Side::new(goal);
+
+    while !forward.frontier.is_empty() && !backward.frontier.is_empty() {
+        let meeting = if forward.frontier.len() <= backward.frontier.len() {
+            forward.expand(&edges, &backward)
+        } else {
+            backward.expand(&reverse_edges, &forward)
+        };
+        if let Some(meeting) = meeting {
+            let mut path = forward.chain(&meeting);
+            path.reverse();
+            let mut cost = path.last().expect("chain includes the meeting node").1;
+            for (state, _) in backward.chain(&meeting).into_iter().skip(1) {
+                cost = cost + C::one();
+                path.push((state, cost));
+            }
+            return path_info_from(path);
+        }
+    }
+    PathInfo {
+        nodes: Store::new(),
+        end_index: None,
+    }
+}
+
+struct Side<S, C>
+where
+    S: Debug + Eq + Hash + Clone,
+    C: Debug + Zero + One + Copy + Hash + Eq,
+{
+    nodes: Store<S, EdgeInfo<C>>,
+    frontier: Vec<usize>,
+}
+
+impl<S, C> Side<S, C>
+where
+    S: Debug + Eq + Hash + Clone,
+    C: Debug + Zero + One + Copy + Hash + Eq + Ord,
+{
+    fn new(start: S) -> Self {
+        let mut nodes = Store::new();
+        let start_info = EdgeInfo {
+            cost: C::zero(),
+            parent: None,
+        };
+        let start_id = nodes.assign(start, start_info).expect("Store starts empty");
+        Self {
+            nodes,
+            frontier: vec![start_id],
+        }
+    }
+
+    /// Expands one full level, returning the meeting state with the lowest combined cost.
+    fn expand<F>(&mut self, edges: &F, other: &Self) -> Option<S>
+    where
+        F: Fn(EdgeInfo<C>, &S) -> Vec<S>,
+    {
+        let mut next_frontier = Vec::new();
+        let mut meeting: Option<(C, S)> = None;
+        for id in std::mem::take(&mut self.frontier) {
+            let (state, &info) = self.nodes.get_entry(id).expect("frontier ids are assigned");
+            let state = state.clone();
+            for next in edges(info, &state) {
+                let next_info = EdgeInfo {
+                    cost: info.cost + C::one(),
+                    parent: Some(id),
+                };
+                let Some(next_id) = self.nodes.assign(next.clone(), next_info) else {
+                    continue;
+                };
+                if let Some(other_id) = other.nodes.get_index(&next) {
+                    let other_cost = other.cost(other_id);
+                    let total = next_info.cost + other_cost;
+                    if meeting.as_ref().is_none_or(|(best, _)| total < *best) {
+                        meeting = Some((total, next));
+                    }
+                }
+                next_frontier.push(next_id);
+            }
+        }
+        self.frontier = next_frontier;
+        meeting.map(|(_, state)| state)
+    }
+
+    /// Walks from `state` back to this side's origin, listing each state with its cost.
+    fn chain(&self, state: &S) -> Vec<(S, C)> {
+        let mut chain = Vec::new();
+        let mut current = self.nodes.get_index(state);
+        while let Some(id) = current {
+            let (state, info) = self.nodes.get_entry(id).expect("parent ids are assigned");
+            chain.push((state.clone(), info.cost));
+            current = info.parent;
+        }
+        chain
+    }
+
+    fn cost(&self, id: usize) -> C {
+        self.nodes.get_entry(id).expect("ids are assigned").1.cost
+    }
+}
+
+/// Depth-first search repeated with a growing depth limit, so that memory stays proportional to
+/// the path length while still finding the shallowest goal. Gives up after `max_depth` steps.
+/// The returned `PathInfo` only holds the nodes along the path.
+pub fn iterative_deepening<S, C, FN, FS>(
+    start: S,
+    edges: FN,
+    end_condition: FS,
+    max_depth: usize,
+) -> PathInfo<S, C>
+where
+    S: Debug + Eq + Hash + Clone,
+    C: Debug + Zero + One + Copy + Hash + Eq + Ord,
+    FN: Fn(EdgeInfo<C>, &S) -> Vec<S>,
+    FS: Fn(EdgeInfo<C>, &S) -> bool,
+{
+    let weighted_edges = |info: EdgeInfo<C>, state: &S| -> Vec<(S, C)> {
+        edges(info, state)
+            .into_iter()
+            .map(|next| (next, C::one()))
+            .collect()
+    };
+    let never_exceeds = |_: C, _: &S| false;
+    let no_heuristic = |_: &S| C::zero();
+    for depth in 0..=max_depth {
+        let mut search = DepthFirst::new(start.clone());
+        if search.limited(&weighted_edges, &end_condition, &never_exceeds, &no_heuristic, depth) {
+            return search.into_path_info();
+        }
+    }
+    PathInfo {
+        nodes: Store::new(),
+        end_index: None,
+    }
+}
+
+/// IDA*: depth-first search bounded by `cost + heuristic`, raising the bound to the smallest
+/// value that exceeded it until the goal is found. `heuristic` must never overestimate the
+/// remaining cost. Gives up once the bound would exceed `max_cost`. The returned `PathInfo` only
+/// holds the nodes along the path.
+pub fn ida_star<S, C, FN, FH, FS>(
+    start: S,
+    edges: FN,
+    heuristic: FH,
+    end_condition: FS,
+    max_cost: C,
+) -> PathInfo<S, C>
+where
+    S: Debug + Eq + Hash + Clone,
+    C: Debug + Zero + One + Copy + Hash + Eq + Ord,
+    FN: Fn(EdgeInfo<C>, &S) -> Vec<(S, C)>,
+    FH: Fn(&S) -> C,
+    FS: Fn(EdgeInfo<C>, &S) -> bool,
+{
+    let mut bound = heuristic(&start);
+    while bound <= max_cost {
+        let mut search = DepthFirst::new(start.clone());
+        let exceeds = |cost: C, next: &S| cost + heuristic(next) > bound;
+        if search.limited(&edges, &end_condition, &exceeds, &heuristic, usize::MAX) {
+            return search.into_path_info();
+        }
+        match search.next_bound {
+            Some(next_bound) => bound = next_bound,
+            None => break,
+        }
+    }
+    PathInfo {
+        nodes: Store::new(),
+        end_index: None,
+    }
+}
+
+/// The current path, stored in order so that each state's id is its depth and the ids handed to
+/// the edge function are the ones the returned `PathInfo` uses.
+struct DepthFirst<S, C>
+where
+    S: Debug + Eq + Hash + Clone,
+    C: Debug + Zero + One + Copy + Hash + Eq,
+{
+    path: Store<S, EdgeInfo<C>>,
+    next_bound: Option<C>,
+}
+
+impl<S, C> DepthFirst<S, C>
+where
+    S: Debug + Eq + Hash + Clone,
+    C: Debug + Zero + One + Copy + Hash + Eq + PartialOrd,
+{
+    fn new(start: S) -> Self {
+        let mut path = Store::new();
+        let start_info = EdgeInfo {
+            cost: C::zero(),
+            parent: None,
+        };
+        path.assign(start, start_info);
+        Self {
+            path,
+            next_bound: None,
+        }
+    }
+
+    fn into_path_info(self) -> PathInfo<S, C> {
+        let end_index = self.path.len().checked_sub(1);
+        PathInfo {
+            nodes: self.path,
+            end_index,
+        }
+    }
+
+    /// Searches below the last state on the path, skipping states already on the path, anything
+    /// deeper than `depth`, and any edge for which `exceeds` holds. The smallest `cost + heuristic`
+    /// among skipped edges is kept as the next bound.
+    fn limited<FN, FS, FX, FH>(
+        &mut self,
+        edges: &FN,
+        end_condition: &FS,
+        exceeds: &FX,
+        heuristic: &FH,
+        depth: usize,
+    ) -> bool
+    where
+        FN: Fn(EdgeInfo<C>, &S) -> Vec<(S, C)>,
+        FS: Fn(EdgeInfo<C>, &S) -> bool,
+        FX: Fn(C, &S) -> bool,
+        FH: Fn(&S) -> C,
+    {
+        let id = self.path.len() - 1;
+        let (state, &info) = self.path.get_entry(id).expect("path always holds the start");
+        let state = state.clone();
+        if end_condition(info, &state) {
+            return true;
+        }
+        if depth == 0 {
+            return false;
+        }
+        for (next, edge_cost) in edges(info, &state) {
+            if self.path.contains(&next) {
+                continue;
+            }
+            let next_cost = info.cost + edge_cost;
+            if exceeds(next_cost, &next) {
+                let estimate = next_cost + heuristic(&next);
+                if self.next_bound.is_none_or(|bound| estimate < bound) {
+                    self.next_bound = Some(estimate);
+                }
+                continue;
+            }
+            let next_info = EdgeInfo {
+                cost: next_cost,
+                parent: Some(id),
+            };
+            self.path.assign(next, next_info);
+            if self.limited(edges, end_condition, exceeds, heuristic, depth - 1) {
+                return true;
+            }
+            self.path.pop();
+        }
+        false
+    }
+}
+
+/// Breadth-first search that only keeps the `width` lowest-scoring states at each depth. Fast and
+/// memory-bounded, but not guaranteed to find the best (or any) path.
+pub fn beam_search<S, C, K, FN, FK, FS>(
+    start: S,
+    edges: FN,
+    score: FK,
+    width: usize,
+    end_condition: FS,
+) -> PathInfo<S, C>
+where
+    S: Debug + Eq + Hash + Clone,
+    C: Debug + Zero + One + Copy + Hash + Eq,
+    K: Ord,
+    FN: Fn(EdgeInfo<C>, &S) -> Vec<(S, C)>,
+    FK: Fn(EdgeInfo<C>, &S) -> K,
+    FS: Fn(EdgeInfo<C>, &S) -> bool,
+{
+    let mut visited = Store::new();
+    let start_info = EdgeInfo {
+        cost: C::zero(),
+        parent: None,
+    };
+    let start_id = visited
+        .assign(start.clone(), start_info)
+        .expect("Store starts empty");
+    let mut beam = vec![(start_id, start_info, start)];
+
+    while !beam.is_empty() {
+        for (id, info, state) in beam.iter() {
+            if end_condition(*info, state) {
+                return PathInfo {
+                    nodes: visited,
+                    end_index: Some(*id),
+                };
+            }
+        }
+
+        let mut candidates: Vec<(K, EdgeInfo<C>, S)> = Vec::new();
+        for (id, info, state) in beam.iter() {
+            for (next, edge_cost) in edges(*info, state) {
+                if visited.contains(&next) {
+                    continue;
+                }
+                let next_info = EdgeInfo {
+                    cost: info.cost + edge_cost,
+                    parent: Some(*id),
+                };
+                candidates.push((score(next_info, &next), next_info, next));
+            }
+        }
+        candidates.sort_by(|a, b| a.0.cmp(&b.0));
+
+        beam = Vec::with_capacity(width);
+        for (_, info, state) in candidates {
+            if beam.len() == width {
+                break;
+            }
+            if let Some(id) = visited.assign(state.clone(), info) {
+                beam.push((id, info, state));
+            }
+        }
+    }
+    PathInfo {
+        nodes: visited,
+        end_index: None,
+    }
+}
+
+/// Builds a `PathInfo` whose `Store` holds exactly the given path, each node parented to the one
+/// before it.
+fn path_info_from<S, C>(path: Vec<(S, C)>) -> PathInfo<S, C>
+where
+    S: Debug + Eq + Hash + Clone,
+    C: Debug + Zero + One + Copy + Hash + Eq,
+{
+    let mut nodes = Store::new();
+    let mut parent = None;
+    for (state, cost) in path {
+        parent = nodes.assign(state, EdgeInfo { cost, parent });
+    }
+    PathInfo {
+        nodes,
+        end_index: parent,
+    }
+}
+
+#[test]
+fn bidirectional_bfs_test() {
+    // numbers reachable by +1 and *2, with the reverse being -1 and /2
+    let result: PathInfo<u32, usize> = bidirectional_bfs(
+        1u32,
+        100,
+        |info, &n| {
+            // only the origin of each side has no parent
+            assert_eq!(n == 1, info.parent.is_none());
+            vec![n + 1, n * 2]
+        },
+        |info, &n| {
+            assert_eq!(n == 100, info.parent.is_none());
+            let mut previous = vec![n - 1];
+            if n % 2 == 0 {
+                previous.push(n / 2);
+            }
+            previous
+        },
+    );
+    assert_eq!(Some(8), result.steps());
+    let states: Vec<u32> = result
+        .path(result.end_index.unwrap())
+        .into_iter()
+        .map(|step| step.state)
+        .collect();
+    assert_eq!(Some(&1), states.first());
+    assert_eq!(Some(&100), states.last());
+    assert_eq!(9, states.len());
+    assert!(states.windows(2).all(|pair| pair[1] == pair[0] + 1 || pair[1] == pair[0] * 2));
+}
+
+#[test]
+fn iterative_deepening_test() {
+    use std::cell::Cell;
+
+    // the parent id seen at the goal points at the previous state of the returned path
+    let goal_parent = Cell::new(None);
+    let end_condition = |info: EdgeInfo<usize>, &n: &u32| {
+        if n == 100 {
+            goal_parent.set(info.parent);
+        }
+        n == 100
+    };
+    let result: PathInfo<u32, usize> =
+        iterative_deepening(1u32, |_, &n| vec![n + 1, n * 2], end_condition, 10);
+    assert_eq!(Some(8), result.steps());
+    let path = result.path(result.end_index.unwrap());
+    let parent = result.nodes.get_entry(goal_parent.get().unwrap()).unwrap().0;
+    assert_eq!(path[path.len() - 2].state, *parent);
+
+    let result: PathInfo<u32, usize> =
+        iterative_deepening(1u32, |_, &n| vec![n + 1, n * 2], |_, &n| n == 100, 7);
+    assert_eq!(None, result.steps());
+}
+
+#[test]
+fn ida_star_test() {
+    use crate::structs::coord::Coord2;
+
+    // walls along x = 2 except at y = 4
+    let goal = Coord2::new2d(4, 0);
+    let edges = |_: EdgeInfo<i64>, pos: &Coord2| {
+        pos.adjacent(false)
+            .into_iter()
+            .filter(|next| next.x() != 2 || next.y() == 4)
+            .filter(|next| (0..5).contains(&next.x()) && (0..5).contains(&next.y()))
+            .map(|next| (next, 1))
+            .collect()
+    };
+    let result = ida_star(
+        Coord2::origin(),
+        edges,
+        |pos| pos.manhattan_distance(goal) as i64,
+        |_, pos| *pos == goal,
+        100,
+    );
+    assert_eq!(Some(12), result.steps());
+}
+
+#[test]
+fn beam_search_test() {
+    // approach 100 greedily by distance, only keeping the two closest states
+    let result: PathInfo<i32, u32> = beam_search(
+        1,
+        |_, &n| vec![(n + 1, 1), (n * 2, 1), (n - 1, 1)],
+        |_, &n| (100 - n).abs(),
+        2,
+        |_, &n| n == 100,
+    );
+    let steps = result.steps().unwrap();
+    assert!(steps >= 8);
+    let end = result.end_index.unwrap();
+    assert_eq!(Some(&100), result.path(end).last().map(|step| &step.state));
+}
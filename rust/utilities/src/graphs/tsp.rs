@@ -0,0 +1,337 @@
+use num_traits::Zero;
+use std::ops::Add;
+
+/// Largest input that `tsp` will hand to Held–Karp before switching to branch-and-bound.
+pub const HELD_KARP_LIMIT: usize = 20;
+
+const UNREACHED: u8 = u8::MAX;
+const ROOT: u8 = u8::MAX - 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Objective {
+    Min,
+    Max,
+}
+
+impl Objective {
+    fn better<C: Ord>(&self, a: C, b: C) -> bool {
+        match self {
+            Objective::Min => a < b,
+            Objective::Max => a > b,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Route {
+    /// Visits every node once without returning to the first.
+    Path,
+    /// Visits every node once and returns to the first. The return leg is included in the cost
+    /// but the first node is not repeated at the end of the ordering.
+    Cycle,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Tour<C> {
+    pub cost: C,
+    pub order: Vec<usize>,
+}
+
+/// Solves the travelling salesman problem over a complete distance matrix, where
+/// `distances[a][b]` is the cost of travelling from `a` to `b`. Cycles start at `start` (or
+/// node 0); paths start at `start` if given, otherwise anywhere.
+///
+/// Uses Held–Karp for up to `HELD_KARP_LIMIT` nodes and branch-and-bound beyond that.
+pub fn tsp<C>(
+    distances: &[Vec<C>],
+    objective: Objective,
+    route: Route,
+    start: Option<usize>,
+) -> Option<Tour<C>>
+where
+    C: Copy + Ord + Zero + Add<Output = C>,
+{
+    if distances.len() <= HELD_KARP_LIMIT {
+        held_karp(distances, objective, route, start)
+    } else {
+        branch_and_bound(distances, objective, route, start)
+    }
+}
+
+/// Bitmask dynamic programming over (visited set, last node). Runs in O(2^n * n^2) time and
+/// O(2^n * n) memory, so it panics above `HELD_KARP_LIMIT` nodes.
+pub fn held_karp<C>(
+    distances: &[Vec<C>],
+    objective: Objective,
+    route: Route,
+    start: Option<usize>,
+) -> Option<Tour<C>>
+where
+    C: Copy + Ord + Zero + Add<Output = C>,
+{
+    let n = distances.len();
+    assert!(n <= HELD_KARP_LIMIT, "Held-Karp is limited to {HELD_KARP_LIMIT} nodes.");
+    if n == 0 {
+        return None;
+    }
+    let start = match route {
+        Route::Cycle => Some(start.unwrap_or(0)),
+        Route::Path => start,
+    };
+
+    let states = 1usize << n;
+    let mut costs = vec![C::zero(); states * n];
+    let mut parents = vec![UNREACHED; states * n];
+    for node in 0..n {
+        if start.is_none_or(|start| start == node) {
+            parents[(1 << node) * n + node] = ROOT;
+        }
+    }
+
+    for mask in 1..states {
+        for (last, row) in distances.iter().enumerate() {
+            let id = mask * n + last;
+            if parents[id] == UNREACHED {
+                continue;
+            }
+            let cost = costs[id];
+            for next in (0..n).filter(|next| mask & (1 << next) == 0) {
+                let next_id = (mask | (1 << next)) * n + next;
+                let next_cost = cost + row[next];
+                if parents[next_id] == UNREACHED || objective.better(next_cost, costs[next_id]) {
+                    costs[next_id] = next_cost;
+                    parents[next_id] = last as u8;
+                }
+            }
+        }
+    }
+
+    let full = states - 1;
+    let (cost, last) = (0..n)
+        .filter(|&last| parents[full * n + last] != UNREACHED)
+        .map(|last| {
+            let cost = costs[full * n + last];
+            match (route, start) {
+                (Route::Cycle, Some(start)) => (cost + distances[last][start], last),
+                _ => (cost, last),
+            }
+        })
+        .reduce(|best, candidate| {
+            if objective.better(candidate.0, best.0) { candidate } else { best }
+        })?;
+
+    let mut order = Vec::with_capacity(n);
+    let mut mask = full;
+    let mut node = last;
+    loop {
+        order.push(node);
+        let parent = parents[mask * n + node];
+        if parent == ROOT {
+            break;
+        }
+        mask ^= 1 << node;
+        node = parent as usize;
+    }
+    order.reverse();
+    Some(Tour { cost, order })
+}
+
+/// Depth-first search that abandons any partial route whose optimistic bound cannot beat the
+/// best complete route found so far. The bound assumes every unvisited node is entered along
+/// its cheapest (or dearest, when maximizing) incoming edge.
+pub fn branch_and_bound<C>(
+    distances: &[Vec<C>],
+    objective: Objective,
+    route: Route,
+    start: Option<usize>,
+) -> Option<Tour<C>>
+where
+    C: Copy + Ord + Zero + Add<Output = C>,
+{
+    let n = distances.len();
+    if n == 0 || start.is_some_and(|start| start >= n) {
+        return None;
+    }
+    let start = match route {
+        Route::Cycle => Some(start.unwrap_or(0)),
+        Route::Path => start,
+    };
+
+    let best_incoming: Vec<C> = (0..n)
+        .map(|to| {
+            (0..n)
+                .filter(|&from| from != to)
+                .map(|from| distances[from][to])
+                .reduce(|a, b| if objective.better(b, a) { b } else { a })
+                .unwrap_or_else(C::zero)
+        })
+        .collect();
+
+    // try the most promising edges first so that good incumbents are found early
+    let preferences: Vec<Vec<usize>> = (0..n)
+        .map(|from| {
+            let mut to: Vec<usize> = (0..n).filter(|&to| to != from).collect();
+            to.sort_by(|&a, &b| match objective {
+                Objective::Min => distances[from][a].cmp(&distances[from][b]),
+                Objective::Max => distances[from][b].cmp(&distances[from][a]),
+            });
+            to
+        })
+        .collect();
+
+    let mut search = BranchAndBound {
+        distances,
+        objective,
+        route,
+        best_incoming,
+        preferences,
+        visited: vec![false; n],
+        order: Vec::with_capacity(n),
+        best: None,
+    };
+    let starts: Vec<usize> = match start {
+        Some(start) => vec![start],
+        None => (0..n).collect(),
+    };
+    for first in starts {
+        search.visited[first] = true;
+        search.order.push(first);
+        let bound = search.remaining_bound(first);
+        search.descend(first, C::zero(), bound);
+        search.order.pop();
+        search.visited[first] = false;
+    }
+    search.best
+}
+
+struct BranchAndBound<'a, C> {
+    distances: &'a [Vec<C>],
+    objective: Objective,
+    route: Route,
+    best_incoming: Vec<C>,
+    preferences: Vec<Vec<usize>>,
+    visited: Vec<bool>,
+    order: Vec<usize>,
+    best: Option<Tour<C>>,
+}
+
+impl<C> BranchAndBound<'_, C>
+where
+    C: Copy + Ord + Zero + Add<Output = C>,
+{
+    fn remaining_bound(&self, first: usize) -> C {
+        let unvisited = (0..self.visited.len())
+            .filter(|&node| !self.visited[node])
+            .fold(C::zero(), |acc, node| acc + self.best_incoming[node]);
+        match self.route {
+            Route::Cycle => unvisited + self.best_incoming[first],
+            Route::Path => unvisited,
+        }
+    }
+
+    fn can_improve(&self, optimistic: C) -> bool {
+        self.best
+            .as_ref()
+            .is_none_or(|best| self.objective.better(optimistic, best.cost))
+    }
+
+    fn descend(&mut self, node: usize, cost: C, bound: C) {
+        if !self.can_improve(cost + bound) {
+            return;
+        }
+        if self.order.len() == self.visited.len() {
+            let cost = match self.route {
+                Route::Cycle => cost + self.distances[node][self.order[0]],
+                Route::Path => cost,
+            };
+            if self.can_improve(cost) {
+                self.best = Some(Tour { cost, order: self.order.clone() });
+            }
+            return;
+        }
+        for index in 0..self.preferences[node].len() {
+            let next = self.preferences[node][index];
+            if self.visited[next] {
+                continue;
+            }
+            self.visited[next] = true;
+            self.order.push(next);
+            let next_bound = self.remaining_bound(self.order[0]);
+            self.descend(next, cost + self.distances[node][next], next_bound);
+            self.order.pop();
+            self.visited[next] = false;
+        }
+    }
+}
+
+#[cfg(test)]
+fn pseudo_random_matrix(n: usize) -> Vec<Vec<u64>> {
+    let mut seed = 0x2545F4914F6CDD1Du64;
+    (0..n)
+        .map(|_| {
+            (0..n)
+                .map(|_| {
+                    seed ^= seed << 13;
+                    seed ^= seed >> 7;
+                    seed ^= seed << 17;
+                    seed % 100
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn santa_route() {
+    // London, Dublin, Belfast
+    let distances = vec![vec![0, 464, 518], vec![464, 0, 141], vec![518, 141, 0]];
+    let shortest = tsp(&distances, Objective::Min, Route::Path, None).unwrap();
+    assert_eq!(605, shortest.cost);
+    assert_eq!(1, shortest.order[1]);
+    let longest = tsp(&distances, Objective::Max, Route::Path, None).unwrap();
+    assert_eq!(982, longest.cost);
+    let from_dublin = tsp(&distances, Objective::Min, Route::Path, Some(1)).unwrap();
+    assert_eq!(1, from_dublin.order[0]);
+    assert_eq!(659, from_dublin.cost);
+}
+
+#[test]
+fn seating() {
+    // Alice, Bob, Carol, David, with mutual happiness summed
+    let happiness: Vec<Vec<i32>> = vec![
+        vec![0, 137, -141, 44],
+        vec![137, 0, 53, -70],
+        vec![-141, 53, 0, 96],
+        vec![44, -70, 96, 0],
+    ];
+    let table = tsp(&happiness, Objective::Max, Route::Cycle, None).unwrap();
+    assert_eq!(330, table.cost);
+    assert_eq!(0, table.order[0]);
+    assert_eq!(4, table.order.len());
+}
+
+#[test]
+fn branch_and_bound_agrees_with_held_karp() {
+    let distances = pseudo_random_matrix(8);
+    for objective in [Objective::Min, Objective::Max] {
+        for route in [Route::Path, Route::Cycle] {
+            for start in [None, Some(3)] {
+                let exact = held_karp(&distances, objective, route, start).unwrap();
+                let bnb = branch_and_bound(&distances, objective, route, start).unwrap();
+                assert_eq!(exact.cost, bnb.cost);
+                let mut order = bnb.order.clone();
+                order.sort_unstable();
+                assert_eq!((0..8).collect::<Vec<_>>(), order);
+            }
+        }
+    }
+}
+
+#[test]
+fn start_outside_the_graph() {
+    let distances = pseudo_random_matrix(4);
+    for route in [Route::Path, Route::Cycle] {
+        assert_eq!(None, held_karp(&distances, Objective::Min, route, Some(4)));
+        assert_eq!(None, branch_and_bound(&distances, Objective::Min, route, Some(4)));
+    }
+}
@@ -0,0 +1,287 @@
+use crate::structs::store::Store;
+use bit_set::BitSet;
+use num_traits::{One, Zero};
+use rustc_hash::FxHashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+use super::{EdgeInfo, PathInfo};
+
+/// Backing storage for the set of states a traversal has already settled. Ids handed out by
+/// `visit` are what `EdgeInfo::parent` points at, so they only need to be meaningful to the
+/// backend that issued them.
+pub trait Visited<S, C>
+where
+    C: Debug + Zero + One + Copy + Hash + Eq,
+{
+    /// Marks `state` as visited, returning its id, or `None` if it was already visited.
+    fn visit(&mut self, state: &S, info: EdgeInfo<C>) -> Option<usize>;
+
+    fn contains(&self, state: &S) -> bool;
+
+    /// Records `cost` as a way to reach the unvisited `state`, returning whether it beats every
+    /// cost recorded before, so weighted traversals only queue improvements. Backends that keep
+    /// no costs accept anything not yet visited, so weighted traversals should use one that
+    /// does, like `WeightedStore` or `DenseVisited`.
+    fn improve(&mut self, state: &S, cost: C) -> bool {
+        let _ = cost;
+        !self.contains(state)
+    }
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<S, C> Visited<S, C> for Store<S, EdgeInfo<C>>
+where
+    S: Eq + Hash + Clone,
+    C: Debug + Zero + One + Copy + Hash + Eq,
+{
+    fn visit(&mut self, state: &S, info: EdgeInfo<C>) -> Option<usize> {
+        self.assign(state.clone(), info)
+    }
+
+    fn contains(&self, state: &S) -> bool {
+        Store::contains(self, state)
+    }
+
+    fn len(&self) -> usize {
+        Store::len(self)
+    }
+}
+
+/// A `Store` of visited states plus the best cost queued so far for each state not yet
+/// visited. The default backend for `dijkstra`.
+#[derive(Clone, Debug)]
+pub struct WeightedStore<S, C>
+where
+    S: Eq + Hash,
+    C: Debug + Zero + One + Copy + Hash + Eq,
+{
+    pub nodes: Store<S, EdgeInfo<C>>,
+    best: FxHashMap<S, C>,
+}
+
+impl<S, C> WeightedStore<S, C>
+where
+    S: Eq + Hash,
+    C: Debug + Zero + One + Copy + Hash + Eq,
+{
+    pub fn new() -> Self {
+        Self {
+            nodes: Store::new(),
+            best: FxHashMap::default(),
+        }
+    }
+}
+
+impl<S, C> Default for WeightedStore<S, C>
+where
+    S: Eq + Hash,
+    C: Debug + Zero + One + Copy + Hash + Eq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S, C> Visited<S, C> for WeightedStore<S, C>
+where
+    S: Eq + Hash + Clone,
+    C: Debug + Zero + One + Copy + Hash + Eq + PartialOrd,
+{
+    fn visit(&mut self, state: &S, info: EdgeInfo<C>) -> Option<usize> {
+        let id = self.nodes.assign(state.clone(), info)?;
+        // settled states never need their queued cost again
+        self.best.remove(state);
+        Some(id)
+    }
+
+    fn contains(&self, state: &S) -> bool {
+        self.nodes.contains(state)
+    }
+
+    fn improve(&mut self, state: &S, cost: C) -> bool {
+        if self.nodes.contains(state) {
+            return false;
+        }
+        match self.best.get_mut(state) {
+            Some(best) if *best <= cost => false,
+            Some(best) => {
+                *best = cost;
+                true
+            }
+            None => {
+                self.best.insert(state.clone(), cost);
+                true
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.nodes.len()
+    }
+}
+
+/// Visited set for states that map onto a dense range of `usize`, such as grid indices. Each
+/// state's id is its slot, so parent pointers and best-known costs are looked up without
+/// hashing or cloning states.
+#[derive(Clone, Debug)]
+pub struct DenseVisited<C, F>
+where
+    C: Debug + Zero + One + Copy + Hash + Eq,
+{
+    slots: Vec<Option<EdgeInfo<C>>>,
+    best: Vec<Option<C>>,
+    key: F,
+    count: usize,
+}
+
+impl<C, F> DenseVisited<C, F>
+where
+    C: Debug + Zero + One + Copy + Hash + Eq,
+{
+    pub fn new(size: usize, key: F) -> Self {
+        Self {
+            slots: vec![None; size],
+            best: vec![None; size],
+            key,
+            count: 0,
+        }
+    }
+
+    pub fn get(&self, slot: usize) -> Option<EdgeInfo<C>> {
+        self.slots.get(slot).copied().flatten()
+    }
+
+    /// Slots from the start of the traversal to `slot`, following parent pointers.
+    pub fn path(&self, slot: usize) -> Vec<usize> {
+        let mut path = Vec::new();
+        let mut current = Some(slot);
+        while let Some(slot) = current {
+            path.push(slot);
+            current = self.get(slot).and_then(|info| info.parent);
+        }
+        path.reverse();
+        path
+    }
+}
+
+impl<S, C, F> Visited<S, C> for DenseVisited<C, F>
+where
+    C: Debug + Zero + One + Copy + Hash + Eq + PartialOrd,
+    F: Fn(&S) -> usize,
+{
+    fn visit(&mut self, state: &S, info: EdgeInfo<C>) -> Option<usize> {
+        let slot = (self.key)(state);
+        if self.slots[slot].is_some() {
+            None
+        } else {
+            self.slots[slot] = Some(info);
+            self.count += 1;
+            Some(slot)
+        }
+    }
+
+    fn contains(&self, state: &S) -> bool {
+        self.slots[(self.key)(state)].is_some()
+    }
+
+    fn improve(&mut self, state: &S, cost: C) -> bool {
+        let slot = (self.key)(state);
+        if self.slots[slot].is_some() || self.best[slot].is_some_and(|best| best <= cost) {
+            return false;
+        }
+        self.best[slot] = Some(cost);
+        true
+    }
+
+    fn len(&self) -> usize {
+        self.count
+    }
+}
+
+/// Visited set that only records reachability, for when neither costs nor paths are needed.
+#[derive(Clone, Debug)]
+pub struct BitVisited<F> {
+    pub reached: BitSet,
+    key: F,
+}
+
+impl<F> BitVisited<F> {
+    pub fn new(size: usize, key: F) -> Self {
+        Self {
+            reached: BitSet::with_capacity(size),
+            key,
+        }
+    }
+}
+
+impl<S, C, F> Visited<S, C> for BitVisited<F>
+where
+    C: Debug + Zero + One + Copy + Hash + Eq,
+    F: Fn(&S) -> usize,
+{
+    fn visit(&mut self, state: &S, _: EdgeInfo<C>) -> Option<usize> {
+        let slot = (self.key)(state);
+        if self.reached.insert(slot) {
+            Some(slot)
+        } else {
+            None
+        }
+    }
+
+    fn contains(&self, state: &S) -> bool {
+        self.reached.contains((self.key)(state))
+    }
+
+    fn len(&self) -> usize {
+        self.reached.len()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SearchStats {
+    /// States popped from the frontier for the first time and handed to the edge function.
+    pub expanded: usize,
+    /// Largest number of entries the frontier held at once.
+    pub peak_frontier: usize,
+    /// Entries popped from the frontier whose state had already been visited.
+    pub duplicates: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct Traversal<V> {
+    pub visited: V,
+    pub end_index: Option<usize>,
+    pub stats: SearchStats,
+}
+
+impl<S, C> From<Traversal<WeightedStore<S, C>>> for PathInfo<S, C>
+where
+    S: Debug + Eq + Hash + Clone,
+    C: Debug + Zero + One + Copy + Hash + Eq,
+{
+    fn from(traversal: Traversal<WeightedStore<S, C>>) -> Self {
+        PathInfo {
+            nodes: traversal.visited.nodes,
+            end_index: traversal.end_index,
+        }
+    }
+}
+
+impl<S, C> From<Traversal<Store<S, EdgeInfo<C>>>> for PathInfo<S, C>
+where
+    S: Debug + Eq + Hash + Clone,
+    C: Debug + Zero + One + Copy + Hash + Eq,
+{
+    fn from(traversal: Traversal<Store<S, EdgeInfo<C>>>) -> Self {
+        PathInfo {
+            nodes: traversal.visited,
+            end_index: traversal.end_index,
+        }
+    }
+}
@@ -1,4 +1,6 @@
 #![allow(dead_code)]
+pub mod automaton;
+pub mod cycles;
 pub mod enums;
 pub mod graphs;
 pub mod math;
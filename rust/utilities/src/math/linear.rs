@@ -0,0 +1,282 @@
+use num_traits::Zero;
+
+use crate::math::rational::Rational;
+use crate::structs::coord::Coord;
+
+/// Outcome of solving a linear system.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LinearSolution<T> {
+    Unique(Vec<T>),
+    /// The equations contradict each other.
+    None,
+    /// The equations are consistent but leave some unknowns free.
+    Infinite,
+}
+
+impl<T> LinearSolution<T> {
+    pub fn unique(self) -> Option<Vec<T>> {
+        match self {
+            LinearSolution::Unique(solution) => Some(solution),
+            _ => None,
+        }
+    }
+}
+
+/// Solves `a * x = b` exactly by Gauss-Jordan elimination. `a` may have any number of rows, so
+/// overdetermined systems are solved when their equations agree.
+pub fn solve(a: &[Vec<Rational>], b: &[Rational]) -> LinearSolution<Rational> {
+    assert_eq!(a.len(), b.len(), "Each equation needs a right-hand side.");
+    let unknowns = a.first().map_or(0, |row| row.len());
+    assert!(
+        a.iter().all(|row| row.len() == unknowns),
+        "Every equation must have the same number of coefficients."
+    );
+    let mut rows: Vec<Vec<Rational>> = a
+        .iter()
+        .zip(b)
+        .map(|(row, &rhs)| row.iter().copied().chain([rhs]).collect())
+        .collect();
+
+    let mut pivots = Vec::new();
+    for col in 0..unknowns {
+        let rank = pivots.len();
+        let Some(pivot) = (rank..rows.len()).find(|&r| !rows[r][col].is_zero()) else {
+            continue;
+        };
+        rows.swap(rank, pivot);
+        let scale = rows[rank][col];
+        for value in rows[rank].iter_mut() {
+            *value = *value / scale;
+        }
+        let pivot_row = rows[rank].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            let factor = row[col];
+            if r == rank || factor.is_zero() {
+                continue;
+            }
+            for (value, &reduced) in row.iter_mut().zip(&pivot_row).skip(col) {
+                *value -= reduced * factor;
+            }
+        }
+        pivots.push(col);
+    }
+
+    if rows[pivots.len()..]
+        .iter()
+        .any(|row| !row[unknowns].is_zero())
+    {
+        LinearSolution::None
+    } else if pivots.len() < unknowns {
+        LinearSolution::Infinite
+    } else {
+        LinearSolution::Unique(
+            rows.iter()
+                .take(unknowns)
+                .map(|row| row[unknowns])
+                .collect(),
+        )
+    }
+}
+
+/// Solves an integer system, keeping only solutions where every unknown is an integer. A unique
+/// fractional solution means there is no integer one.
+pub fn solve_integer(a: &[Vec<i128>], b: &[i128]) -> LinearSolution<i128> {
+    let a: Vec<Vec<Rational>> = a
+        .iter()
+        .map(|row| row.iter().map(|&value| Rational::from(value)).collect())
+        .collect();
+    let b: Vec<Rational> = b.iter().map(|&value| Rational::from(value)).collect();
+    match solve(&a, &b) {
+        LinearSolution::Unique(solution) => solution
+            .iter()
+            .map(Rational::to_integer)
+            .collect::<Option<Vec<_>>>()
+            .map_or(LinearSolution::None, LinearSolution::Unique),
+        LinearSolution::None => LinearSolution::None,
+        LinearSolution::Infinite => LinearSolution::Infinite,
+    }
+}
+
+/// Solves two equations in two unknowns by Cramer's rule, with no intermediate fractions.
+pub fn cramer_2x2(a: [[i128; 2]; 2], b: [i128; 2]) -> LinearSolution<Rational> {
+    let det = a[0][0] * a[1][1] - a[0][1] * a[1][0];
+    let det_x = b[0] * a[1][1] - a[0][1] * b[1];
+    let det_y = a[0][0] * b[1] - b[0] * a[1][0];
+    match (det, det_x, det_y) {
+        (0, 0, 0) => LinearSolution::Infinite,
+        (0, _, _) => LinearSolution::None,
+        _ => LinearSolution::Unique(vec![Rational::new(det_x, det), Rational::new(det_y, det)]),
+    }
+}
+
+/// How the lines `p1 + t * d1` and `p2 + u * d2` meet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LineIntersection<const N: usize> {
+    /// They cross once, at `point`, reached at parameters `t` and `u`.
+    Point {
+        point: [Rational; N],
+        t: Rational,
+        u: Rational,
+    },
+    /// They are the same line.
+    Coincident,
+    /// They run in the same direction without meeting.
+    Parallel,
+    /// They neither meet nor run parallel, which is only possible above two dimensions.
+    Skew,
+}
+
+/// Intersects two lines given as a point and a direction, exactly. Panics if either direction
+/// is zero, since that describes a point rather than a line.
+pub fn line_intersection<const N: usize>(
+    p1: Coord<i64, N>,
+    d1: Coord<i64, N>,
+    p2: Coord<i64, N>,
+    d2: Coord<i64, N>,
+) -> LineIntersection<N> {
+    assert!(
+        d1 != Coord::origin() && d2 != Coord::origin(),
+        "Line directions must be non-zero."
+    );
+    // p1 + t * d1 = p2 + u * d2, one equation per axis
+    let a: Vec<Vec<Rational>> = (0..N)
+        .map(|axis| vec![Rational::from(d1.0[axis]), Rational::from(-d2.0[axis])])
+        .collect();
+    let b: Vec<Rational> = (0..N)
+        .map(|axis| Rational::from(p2.0[axis] as i128 - p1.0[axis] as i128))
+        .collect();
+    match solve(&a, &b) {
+        LinearSolution::Unique(params) => {
+            let (t, u) = (params[0], params[1]);
+            let point = std::array::from_fn(|axis| {
+                Rational::from(p1.0[axis]) + t * Rational::from(d1.0[axis])
+            });
+            LineIntersection::Point { point, t, u }
+        }
+        LinearSolution::Infinite => LineIntersection::Coincident,
+        LinearSolution::None => {
+            let parallel = matches!(
+                solve(&a, &vec![Rational::zero(); N]),
+                LinearSolution::Infinite
+            );
+            if parallel {
+                LineIntersection::Parallel
+            } else {
+                LineIntersection::Skew
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::coord::{Coord2, Coord3};
+
+    #[test]
+    fn claw_machines() {
+        // y24d13 examples: a presses of A and b presses of B reach the prize
+        let machine = |a: (i128, i128), b: (i128, i128), prize: [i128; 2]| {
+            solve_integer(&[vec![a.0, b.0], vec![a.1, b.1]], &prize)
+        };
+        assert_eq!(
+            LinearSolution::Unique(vec![80, 40]),
+            machine((94, 34), (22, 67), [8400, 5400])
+        );
+        assert_eq!(
+            LinearSolution::None,
+            machine((26, 66), (67, 21), [12748, 12176])
+        );
+        assert_eq!(
+            LinearSolution::Unique(vec![Rational::from(80i128), Rational::from(40i128)]),
+            cramer_2x2([[94, 22], [34, 67]], [8400, 5400])
+        );
+        assert_eq!(LinearSolution::None, cramer_2x2([[1, 2], [2, 4]], [3, 7]));
+        assert_eq!(
+            LinearSolution::Infinite,
+            cramer_2x2([[1, 2], [2, 4]], [3, 6])
+        );
+    }
+
+    #[test]
+    fn elimination() {
+        let r = |n: i128| Rational::from(n);
+        let a = vec![
+            vec![r(2), r(1), r(-1)],
+            vec![r(-3), r(-1), r(2)],
+            vec![r(-2), r(1), r(2)],
+        ];
+        let b = vec![r(8), r(-11), r(-3)];
+        assert_eq!(
+            LinearSolution::Unique(vec![r(2), r(3), r(-1)]),
+            solve(&a, &b)
+        );
+        let dependent = vec![vec![r(1), r(1)], vec![r(2), r(2)]];
+        assert_eq!(LinearSolution::Infinite, solve(&dependent, &[r(1), r(2)]));
+        assert_eq!(LinearSolution::None, solve(&dependent, &[r(1), r(3)]));
+    }
+
+    #[test]
+    fn hailstones() {
+        let stones: [([i128; 3], [i128; 3]); 5] = [
+            ([19, 13, 30], [-2, 1, -2]),
+            ([18, 19, 22], [-1, -1, -2]),
+            ([20, 25, 34], [-2, -2, -4]),
+            ([12, 31, 28], [-1, -2, -1]),
+            ([20, 19, 15], [1, -5, -3]),
+        ];
+
+        // y23d24 part one: paths cross in the xy-plane at x=14.333, y=15.333
+        let flat = |i: usize| {
+            let (p, v) = (stones[i].0.map(|c| c as i64), stones[i].1.map(|c| c as i64));
+            (Coord2::new2d(p[0], p[1]), Coord2::new2d(v[0], v[1]))
+        };
+        let ((p1, d1), (p2, d2)) = (flat(0), flat(1));
+        let LineIntersection::Point { point, t, u } = line_intersection(p1, d1, p2, d2) else {
+            panic!("paths should cross");
+        };
+        assert_eq!([Rational::new(43, 3), Rational::new(46, 3)], point);
+        assert!(t > Rational::zero() && u > Rational::zero());
+        let ((p1, d1), (p2, d2)) = (flat(1), flat(2));
+        assert_eq!(
+            LineIntersection::Parallel,
+            line_intersection(p1, d1, p2, d2)
+        );
+
+        // y23d24 part two: the rock's path satisfies (p_i - P) x (v_i - V) = 0 for every stone.
+        // Subtracting pairs of stones cancels the P x V term, leaving linear equations in the
+        // xy-plane for X, Y, VX and VY.
+        let mut a = Vec::new();
+        let mut b = Vec::new();
+        for i in 0..stones.len() {
+            for j in i + 1..stones.len() {
+                let ([xi, yi, _], [vxi, vyi, _]) = stones[i];
+                let ([xj, yj, _], [vxj, vyj, _]) = stones[j];
+                a.push(vec![vyj - vyi, vxi - vxj, yi - yj, xj - xi]);
+                b.push((xj * vyj - yj * vxj) - (xi * vyi - yi * vxi));
+            }
+        }
+        assert_eq!(
+            LinearSolution::Unique(vec![24, 13, -3, 1]),
+            solve_integer(&a, &b)
+        );
+
+        // in three dimensions, lines can miss without being parallel
+        let skew = line_intersection(
+            Coord3::new3d(0, 0, 0),
+            Coord3::new3d(1, 0, 0),
+            Coord3::new3d(0, 1, 1),
+            Coord3::new3d(0, 1, 0),
+        );
+        assert_eq!(LineIntersection::Skew, skew);
+        let crossing = line_intersection(
+            Coord3::new3d(0, 0, 0),
+            Coord3::new3d(1, 1, 1),
+            Coord3::new3d(4, 0, 2),
+            Coord3::new3d(-1, 1, 0),
+        );
+        assert!(
+            matches!(crossing, LineIntersection::Point { point, .. } if point == [Rational::from(2i128); 3])
+        );
+    }
+}
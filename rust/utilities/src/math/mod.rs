@@ -1 +1,6 @@
 pub mod formulae;
+pub mod linear;
+pub mod modular;
+pub mod polynomial;
+pub mod primes;
+pub mod rational;
@@ -0,0 +1,207 @@
+//! Modular arithmetic on `i64`, widening to `i128` for intermediate products so that moduli up
+//! to `i64::MAX` never overflow. Results are always normalized into `0..m`. Multiplication and
+//! powers also come in `u64` versions, widening to `u128`, for moduli up to `u64::MAX`.
+
+/// `a + b mod m`.
+pub fn mod_add(a: i64, b: i64, m: i64) -> i64 {
+    (a as i128 + b as i128).rem_euclid(m as i128) as i64
+}
+
+/// `a * b mod m`.
+pub fn mod_mul(a: i64, b: i64, m: i64) -> i64 {
+    (a as i128 * b as i128).rem_euclid(m as i128) as i64
+}
+
+/// `base ^ exp mod m`, by repeated squaring.
+pub fn mod_pow(base: i64, exp: u64, m: i64) -> i64 {
+    pow_by_squaring(base.rem_euclid(m), exp, 1 % m, |a, b| mod_mul(a, b, m))
+}
+
+/// `a * b mod m` on `u64`.
+pub fn mod_mul_u64(a: u64, b: u64, m: u64) -> u64 {
+    (a as u128 * b as u128 % m as u128) as u64
+}
+
+/// `base ^ exp mod m` on `u64`.
+pub fn mod_pow_u64(base: u64, exp: u64, m: u64) -> u64 {
+    pow_by_squaring(base % m, exp, 1 % m, |a, b| mod_mul_u64(a, b, m))
+}
+
+fn pow_by_squaring<T: Copy>(mut base: T, mut exp: u64, one: T, mul: impl Fn(T, T) -> T) -> T {
+    let mut result = one;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul(result, base);
+        }
+        base = mul(base, base);
+        exp >>= 1;
+    }
+    result
+}
+
+/// Extended Euclid: returns `(g, x, y)` where `g = gcd(a, b) >= 0` and `a * x + b * y = g`.
+pub fn ext_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut r, mut new_r) = (a as i128, b as i128);
+    let (mut x, mut new_x) = (1i128, 0i128);
+    let (mut y, mut new_y) = (0i128, 1i128);
+    while new_r != 0 {
+        let quotient = r / new_r;
+        (r, new_r) = (new_r, r - quotient * new_r);
+        (x, new_x) = (new_x, x - quotient * new_x);
+        (y, new_y) = (new_y, y - quotient * new_y);
+    }
+    if r < 0 {
+        (r, x, y) = (-r, -x, -y);
+    }
+    (r as i64, x as i64, y as i64)
+}
+
+/// The `x` in `0..m` with `a * x = 1 mod m`, if `a` and `m` are coprime.
+pub fn mod_inv(a: i64, m: i64) -> Option<i64> {
+    let (g, x, _) = ext_gcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+/// Combines `x = a1 mod m1` and `x = a2 mod m2` into a single congruence modulo
+/// `lcm(m1, m2)`. The moduli need not be coprime; returns `None` if the congruences conflict.
+pub fn crt_pair((a1, m1): (i64, i64), (a2, m2): (i64, i64)) -> Option<(i64, i64)> {
+    let (g, p, _) = ext_gcd(m1, m2);
+    let difference = a2 as i128 - a1 as i128;
+    if difference % g as i128 != 0 {
+        return None;
+    }
+    let lcm = m1 as i128 / g as i128 * m2 as i128;
+    // x = a1 + m1 * k, where m1 * k = a2 - a1 mod m2
+    let step = m2 as i128 / g as i128;
+    let k = (difference / g as i128 * p as i128).rem_euclid(step);
+    let x = (a1 as i128 + m1 as i128 * k).rem_euclid(lcm);
+    Some((i64::try_from(x).ok()?, i64::try_from(lcm).ok()?))
+}
+
+/// Solves a system of `(residue, modulus)` congruences, returning the smallest non-negative
+/// solution and the combined modulus. `None` if the system has no solution or the combined
+/// modulus does not fit in an `i64`.
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    congruences
+        .iter()
+        .try_fold((0, 1), |acc, &(a, m)| crt_pair(acc, (a.rem_euclid(m), m)))
+}
+
+/// The map `x -> a * x + c (mod M)`, such as a deck shuffle or a linear congruential generator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Affine<const M: i64> {
+    pub a: i64,
+    pub c: i64,
+}
+
+impl<const M: i64> Affine<M> {
+    pub fn new(a: i64, c: i64) -> Self {
+        Self {
+            a: a.rem_euclid(M),
+            c: c.rem_euclid(M),
+        }
+    }
+
+    pub fn identity() -> Self {
+        Self::new(1, 0)
+    }
+
+    pub fn apply(&self, x: i64) -> i64 {
+        mod_add(mod_mul(self.a, x, M), self.c, M)
+    }
+
+    /// The map applying `self` and then `other`.
+    pub fn then(&self, other: &Self) -> Self {
+        Self::new(
+            mod_mul(other.a, self.a, M),
+            mod_add(mod_mul(other.a, self.c, M), other.c, M),
+        )
+    }
+
+    /// The map undoing `self`, if `a` is invertible modulo `M`.
+    pub fn inverse(&self) -> Option<Self> {
+        let a = mod_inv(self.a, M)?;
+        Some(Self::new(a, -mod_mul(a, self.c, M)))
+    }
+
+    /// `self` applied `n` times, in `O(log n)` compositions.
+    pub fn pow(&self, mut n: u64) -> Self {
+        let mut base = *self;
+        let mut result = Self::identity();
+        while n > 0 {
+            if n & 1 == 1 {
+                result = result.then(&base);
+            }
+            base = base.then(&base);
+            n >>= 1;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let big = 119_315_717_514_047;
+        assert_eq!(mod_mul(big - 1, big - 1, big), 1);
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(-3, 3, 10), 3);
+        assert_eq!(mod_pow(5, 0, 1), 0);
+        assert_eq!(ext_gcd(240, 46), (2, -9, 47));
+        assert_eq!(mod_inv(3, 11), Some(4));
+        assert_eq!(mod_inv(-3, 11), Some(7));
+        assert_eq!(mod_inv(4, 12), None);
+        let inverse = mod_inv(big - 2, big).unwrap();
+        assert_eq!(mod_mul(inverse, big - 2, big), 1);
+    }
+
+    #[test]
+    fn chinese_remainder() {
+        // y20d13 example: bus `id` departs `offset` minutes after t
+        let buses = [(7, 0), (13, 1), (59, 4), (31, 6), (19, 7)];
+        let congruences: Vec<(i64, i64)> =
+            buses.iter().map(|&(id, offset)| (-offset, id)).collect();
+        assert_eq!(Some((1_068_781, 7 * 13 * 59 * 31 * 19)), crt(&congruences));
+
+        assert_eq!(Some((10, 12)), crt(&[(2, 4), (4, 6)]));
+        assert_eq!(None, crt(&[(1, 4), (2, 6)]));
+        assert_eq!(Some((0, 1)), crt(&[]));
+    }
+
+    #[test]
+    fn affine() {
+        // y19d22 example: where each card of a 10-card deck ends up
+        const CARDS: i64 = 10;
+        let increment = Affine::<CARDS>::new(7, 0);
+        let new_stack = Affine::<CARDS>::new(-1, -1);
+        let cut = |n: i64| Affine::<CARDS>::new(1, -n);
+        let shuffle = increment.then(&new_stack).then(&new_stack);
+        let deck: Vec<i64> = {
+            let inverse = shuffle.inverse().unwrap();
+            (0..CARDS).map(|pos| inverse.apply(pos)).collect()
+        };
+        assert_eq!(vec![0, 3, 6, 9, 2, 5, 8, 1, 4, 7], deck);
+        assert_eq!(2, cut(3).then(&new_stack).apply(0));
+
+        let lcg = Affine::<1_000_003>::new(48_271, 12_345);
+        let stepped = (0..1_000).fold(42, |x, _| lcg.apply(x));
+        assert_eq!(stepped, lcg.pow(1_000).apply(42));
+        assert_eq!(42, lcg.pow(1_000).inverse().unwrap().apply(stepped));
+        assert_eq!(Affine::identity(), lcg.then(&lcg.inverse().unwrap()));
+        assert_eq!(None, Affine::<12>::new(4, 1).inverse());
+
+        // sums of residues near i64::MAX still fit
+        const HUGE: i64 = i64::MAX - 24;
+        let negate = Affine::<HUGE>::new(-1, -1);
+        assert_eq!(HUGE - 2, mod_add(HUGE - 1, HUGE - 1, HUGE));
+        assert_eq!(0, negate.apply(HUGE - 1));
+        assert_eq!(Affine::identity(), negate.then(&negate));
+        assert_eq!(HUGE - 1, negate.pow(3).apply(0));
+        let back = Affine::<HUGE>::new(1, -1);
+        assert_eq!(HUGE - 2, back.apply(HUGE - 1));
+        assert_eq!(Affine::new(1, -2), back.then(&back));
+    }
+}
@@ -0,0 +1,162 @@
+//! Polynomial sequences: finite differences over evenly spaced samples, and Lagrange
+//! interpolation through arbitrary sample points. Arithmetic is exact: differences are checked,
+//! and extrapolation widens to `i128` or `Rational` internally.
+
+use crate::math::rational::Rational;
+
+/// Differences between consecutive values, or `None` if one overflows an `i64`.
+pub fn differences(sequence: &[i64]) -> Option<Vec<i64>> {
+    sequence
+        .windows(2)
+        .map(|pair| pair[1].checked_sub(pair[0]))
+        .collect()
+}
+
+/// The difference table: the sequence itself, then each row of differences, ending with the
+/// first row that is all zeros. `None` if the samples run out before a row of zeros appears,
+/// meaning they are not enough to show the sequence is polynomial, or if a difference overflows.
+pub fn difference_table(sequence: &[i64]) -> Option<Vec<Vec<i64>>> {
+    let mut table = vec![sequence.to_vec()];
+    loop {
+        let last = table.last().expect("the table starts with the sequence");
+        if last.is_empty() {
+            return None;
+        }
+        if last.iter().all(|&value| value == 0) {
+            return Some(table);
+        }
+        table.push(differences(last)?);
+    }
+}
+
+/// Degree of the polynomial the sequence follows, or `None` if the samples do not show one.
+/// A constant sequence, including all zeros, has degree zero.
+pub fn degree(sequence: &[i64]) -> Option<usize> {
+    let rows = difference_table(sequence)?.len();
+    Some(rows.saturating_sub(2))
+}
+
+/// Value at index `n` of the polynomial sequence whose first values are `sequence`, found by
+/// Newton's forward difference formula. `n` may lie before, inside or beyond the samples.
+/// `None` if the sequence is not polynomial within the samples or the value overflows an `i64`.
+pub fn extrapolate(sequence: &[i64], n: i64) -> Option<i64> {
+    let table = difference_table(sequence)?;
+    newton(table.iter().map(|row| row[0]), n)
+}
+
+/// Like `extrapolate`, but trusts that the sequence has the given degree instead of checking,
+/// so `degree + 1` samples are enough. This suits puzzles whose growth is known to be
+/// quadratic, like y23d21. `None` if there are too few samples or the value overflows.
+pub fn extrapolate_with_degree(sequence: &[i64], degree: usize, n: i64) -> Option<i64> {
+    if sequence.len() <= degree {
+        return None;
+    }
+    let leading: Vec<i64> = std::iter::successors(Some(sequence.to_vec()), |row| differences(row))
+        .take(degree + 1)
+        .map(|row| row[0])
+        .collect();
+    if leading.len() <= degree {
+        return None;
+    }
+    newton(leading.into_iter(), n)
+}
+
+/// Sums `C(n, k) * leading[k]`, where `leading` holds the first value of each difference row.
+fn newton(leading: impl Iterator<Item = i64>, n: i64) -> Option<i64> {
+    let n = n as i128;
+    let mut binomial = 1i128;
+    let mut value = 0i128;
+    for (k, first) in leading.enumerate() {
+        value = value.checked_add(binomial.checked_mul(first as i128)?)?;
+        // C(n, k + 1) = C(n, k) * (n - k) / (k + 1), which always divides exactly
+        binomial = binomial.checked_mul(n - k as i128)? / (k as i128 + 1);
+    }
+    i64::try_from(value).ok()
+}
+
+/// The value following the samples.
+pub fn next_value(sequence: &[i64]) -> Option<i64> {
+    extrapolate(sequence, sequence.len() as i64)
+}
+
+/// The value preceding the samples.
+pub fn previous_value(sequence: &[i64]) -> Option<i64> {
+    extrapolate(sequence, -1)
+}
+
+/// Value at `x` of the lowest-degree polynomial through `points`, which must have distinct `x`
+/// coordinates.
+pub fn lagrange(points: &[(i64, i64)], x: i64) -> Rational {
+    points
+        .iter()
+        .enumerate()
+        .map(|(i, &(xi, yi))| {
+            points.iter().enumerate().filter(|&(j, _)| j != i).fold(
+                Rational::from(yi),
+                |term, (_, &(xj, _))| {
+                    assert_ne!(xi, xj, "Interpolation points must have distinct x.");
+                    term * Rational::new(x as i128 - xj as i128, xi as i128 - xj as i128)
+                },
+            )
+        })
+        .sum()
+}
+
+/// `lagrange`, when the result is an integer.
+pub fn lagrange_integer(points: &[(i64, i64)], x: i64) -> Option<i64> {
+    i64::try_from(lagrange(points, x).to_integer()?).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn oasis() {
+        // y23d09 examples
+        let histories = [
+            vec![0, 3, 6, 9, 12, 15],
+            vec![1, 3, 6, 10, 15, 21],
+            vec![10, 13, 16, 21, 30, 45],
+        ];
+        let next: Vec<i64> = histories.iter().map(|h| next_value(h).unwrap()).collect();
+        let previous: Vec<i64> = histories
+            .iter()
+            .map(|h| previous_value(h).unwrap())
+            .collect();
+        assert_eq!(vec![18, 28, 68], next);
+        assert_eq!(vec![-3, 0, 5], previous);
+        assert_eq!(
+            vec![Some(1), Some(2), Some(3)],
+            histories.iter().map(|h| degree(h)).collect::<Vec<_>>()
+        );
+
+        assert_eq!(Some(0), degree(&[7, 7]));
+        assert_eq!(Some(0), degree(&[0]));
+        assert_eq!(None, degree(&[1, 2, 4, 8, 16]));
+        assert_eq!(None, next_value(&[]));
+
+        assert_eq!(Some(vec![3, -1]), differences(&[1, 4, 3]));
+        assert_eq!(None, differences(&[i64::MIN, i64::MAX]));
+        assert_eq!(None, degree(&[i64::MIN, i64::MAX, i64::MIN]));
+    }
+
+    #[test]
+    fn step_counter() {
+        // y23d21 samples a quadratic every 131 steps from step 65
+        let f = |x: i64| 3 * x * x - 7 * x + 11;
+        let samples = [f(0), f(1), f(2)];
+        assert_eq!(None, extrapolate(&samples, 202_300));
+        assert_eq!(
+            Some(f(202_300)),
+            extrapolate_with_degree(&samples, 2, 202_300)
+        );
+        assert_eq!(Some(f(-5)), extrapolate(&[f(0), f(1), f(2), f(3)], -5));
+        assert_eq!(None, extrapolate_with_degree(&samples, 3, 10));
+
+        let points: Vec<(i64, i64)> = [65, 196, 327].iter().map(|&x| (x, f(x))).collect();
+        assert_eq!(Some(f(26_501_365)), lagrange_integer(&points, 26_501_365));
+        assert_eq!(Rational::new(1, 2), lagrange(&[(0, 0), (2, 1)], 1));
+        assert_eq!(None, lagrange_integer(&[(0, 0), (2, 1)], 1));
+    }
+}
@@ -0,0 +1,257 @@
+//! Primes and multiplicative functions on `u64`.
+
+use crate::math::{
+    formulae::gcd,
+    modular::{mod_mul_u64, mod_pow_u64},
+};
+
+/// Every prime up to and including `limit`, by the sieve of Eratosthenes.
+pub fn primes_up_to(limit: u64) -> Vec<u64> {
+    let limit = limit as usize;
+    let mut composite = vec![false; limit + 1];
+    let mut primes = Vec::new();
+    for n in 2..=limit {
+        if composite[n] {
+            continue;
+        }
+        primes.push(n as u64);
+        for multiple in (n * n..=limit).step_by(n) {
+            composite[multiple] = true;
+        }
+    }
+    primes
+}
+
+/// Every prime in `low..high`, sieving one fixed-size block at a time so that memory stays
+/// small however far from zero the range lies. Safe up to `u64::MAX`, though the base primes
+/// below `sqrt(high)` must still fit in memory.
+pub fn segmented_sieve(low: u64, high: u64) -> Vec<u64> {
+    const BLOCK: u64 = 1 << 16;
+    let base = primes_up_to(high.isqrt());
+    let mut primes = Vec::new();
+    let mut start = low.max(2);
+    while start < high {
+        let end = high.min(start.saturating_add(BLOCK));
+        let mut composite = vec![false; (end - start) as usize];
+        for &p in &base {
+            // no multiple of p at or above start fits in a u64, so none lies in the block
+            let Some(first) = start.div_ceil(p).checked_mul(p) else {
+                continue;
+            };
+            let first = first.max(p * p);
+            for multiple in (first..end).step_by(p as usize) {
+                composite[(multiple - start) as usize] = true;
+            }
+        }
+        primes.extend(
+            composite
+                .iter()
+                .enumerate()
+                .filter(|&(_, &c)| !c)
+                .map(|(offset, _)| start + offset as u64),
+        );
+        start = end;
+    }
+    primes
+}
+
+/// Deterministic Miller-Rabin, exact for every `u64`.
+pub fn is_prime(n: u64) -> bool {
+    const WITNESSES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    if n < 2 {
+        return false;
+    }
+    if let Some(&p) = WITNESSES.iter().find(|&&p| n.is_multiple_of(p)) {
+        return n == p;
+    }
+    let shift = (n - 1).trailing_zeros();
+    let odd = (n - 1) >> shift;
+    WITNESSES.iter().all(|&witness| {
+        let mut x = mod_pow_u64(witness, odd, n);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        (1..shift).any(|_| {
+            x = mod_mul_u64(x, x, n);
+            x == n - 1
+        })
+    })
+}
+
+/// Some non-trivial factor of the odd composite `n`, by Pollard's rho.
+fn pollard_rho(n: u64) -> u64 {
+    for c in 1.. {
+        let f = |x: u64| ((x as u128 * x as u128 + c as u128) % n as u128) as u64;
+        let (mut x, mut y, mut d) = (2, 2, 1);
+        while d == 1 {
+            x = f(x);
+            y = f(f(y));
+            d = gcd(x.abs_diff(y), n);
+        }
+        if d != n {
+            return d;
+        }
+    }
+    unreachable!("some increment always finds a factor")
+}
+
+/// Prime factors of `n` with their exponents, in ascending order. Small factors are found by
+/// trial division and any that remain by Pollard's rho. Zero and one have no factors.
+pub fn factorize(n: u64) -> Vec<(u64, u32)> {
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut factors: Vec<u64> = Vec::new();
+    let mut remaining = n;
+    for p in [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37] {
+        while remaining.is_multiple_of(p) {
+            factors.push(p);
+            remaining /= p;
+        }
+    }
+    let mut stack = vec![remaining];
+    while let Some(m) = stack.pop() {
+        if m == 1 {
+            continue;
+        }
+        if is_prime(m) {
+            factors.push(m);
+        } else {
+            let d = pollard_rho(m);
+            stack.extend([d, m / d]);
+        }
+    }
+    factors.sort_unstable();
+    factors
+        .chunk_by(|a, b| a == b)
+        .map(|run| (run[0], run.len() as u32))
+        .collect()
+}
+
+/// Every divisor of `n` in ascending order.
+pub fn divisors(n: u64) -> Vec<u64> {
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut divisors = vec![1];
+    for (p, exponent) in factorize(n) {
+        let smaller = divisors.len();
+        let mut power = 1;
+        for _ in 0..exponent {
+            power *= p;
+            for i in 0..smaller {
+                divisors.push(divisors[i] * power);
+            }
+        }
+    }
+    divisors.sort_unstable();
+    divisors
+}
+
+/// Sum of the `k`th powers of the divisors of `n`, so `sigma(n, 0)` counts the divisors and
+/// `sigma(n, 1)` adds them up. `None` if the sum, or any power along the way, overflows a
+/// `u64`.
+pub fn sigma(n: u64, k: u32) -> Option<u64> {
+    factorize(n)
+        .into_iter()
+        .try_fold(1u64, |product, (p, exponent)| {
+            let step = p.checked_pow(k)?;
+            let (mut power, mut sum) = (1u64, 1u64);
+            for _ in 0..exponent {
+                power = power.checked_mul(step)?;
+                sum = sum.checked_add(power)?;
+            }
+            product.checked_mul(sum)
+        })
+}
+
+/// Euler's totient: how many of `1..=n` are coprime to `n`.
+pub fn totient(n: u64) -> u64 {
+    factorize(n)
+        .into_iter()
+        .fold(n, |result, (p, _)| result / p * (p - 1))
+}
+
+/// `sigma(n, 1)` for every `n` in `0..=limit` at once, by adding each divisor to its
+/// multiples. Since `sigma(n, 1) >= n`, the first `n` with a divisor sum of at least `target`
+/// is never above `target`, which bounds searches like y15d20.
+pub fn divisor_sum_sieve(limit: usize) -> Vec<u64> {
+    limited_divisor_sum_sieve(limit, usize::MAX)
+}
+
+/// `divisor_sum_sieve`, except each divisor only counts towards its first `multiples`
+/// multiples, as when y15d20's elves stop after fifty houses.
+pub fn limited_divisor_sum_sieve(limit: usize, multiples: usize) -> Vec<u64> {
+    let mut sums = vec![0; limit + 1];
+    for divisor in 1..=limit {
+        for n in (divisor..=limit).step_by(divisor).take(multiples) {
+            sums[n] += divisor as u64;
+        }
+    }
+    sums
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sieves() {
+        let primes = primes_up_to(100);
+        assert_eq!(25, primes.len());
+        assert_eq!(Some(&97), primes.last());
+        assert_eq!(primes, segmented_sieve(0, 101));
+        assert!(primes.iter().all(|&p| is_prime(p)));
+        assert_eq!(75, (1..=100).filter(|&n| !is_prime(n)).count());
+
+        let far: Vec<u64> = (1_000_000_000_000..1_000_000_200_000)
+            .filter(|&n| is_prime(n))
+            .collect();
+        assert_eq!(far, segmented_sieve(1_000_000_000_000, 1_000_000_200_000));
+        assert!(is_prime(u64::MAX - 58));
+        assert!(!is_prime(3_215_031_751));
+    }
+
+    #[test]
+    fn factors() {
+        assert_eq!(
+            vec![(71, 1), (839, 1), (1471, 1), (6857, 1)],
+            factorize(600_851_475_143)
+        );
+        assert_eq!(
+            vec![(998_244_353, 1), (1_000_000_007, 1)],
+            factorize(998_244_353 * 1_000_000_007)
+        );
+        assert_eq!(vec![(2, 3), (3, 2)], factorize(72));
+        assert!(factorize(1).is_empty());
+        assert_eq!(vec![1, 2, 3, 4, 6, 9, 12, 18, 36], divisors(36));
+        assert_eq!((9, 91), (sigma(36, 0).unwrap(), sigma(36, 1).unwrap()));
+        assert_eq!(
+            Some(1 + 4 + 9 + 16 + 36 + 81 + 144 + 324 + 1296),
+            sigma(36, 2)
+        );
+        assert_eq!(None, sigma(36, 40));
+        assert_eq!(None, sigma(u64::MAX - 58, 2));
+        assert_eq!((12, 96, 1), (totient(36), totient(97), totient(1)));
+        for n in 1..500 {
+            let brute: u64 = (1..=n).filter(|d| n % d == 0).sum();
+            assert_eq!(Some(brute), sigma(n, 1));
+        }
+    }
+
+    #[test]
+    fn presents() {
+        // y15d20: house n gets ten presents from each elf that divides it
+        let sums = divisor_sum_sieve(9);
+        let presents: Vec<u64> = sums[1..].iter().map(|&sum| sum * 10).collect();
+        assert_eq!(vec![10, 30, 40, 70, 60, 120, 80, 150, 130], presents);
+        assert_eq!(
+            Some(8),
+            presents.iter().position(|&p| p >= 150).map(|i| i + 1)
+        );
+
+        let limited = limited_divisor_sum_sieve(120, 50);
+        assert_eq!(sigma(120, 1), Some(limited[120] + 1 + 2));
+        assert_eq!(sigma(100, 1), Some(limited[100] + 1));
+    }
+}
@@ -0,0 +1,248 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+use num_traits::{One, Zero};
+
+use crate::math::formulae::gcd;
+
+/// An exact fraction of `i128`s, kept in lowest terms with a positive denominator so that equal
+/// values compare and hash equal. Arithmetic cancels common factors before multiplying, but
+/// still panics on overflow like plain integer arithmetic.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rational {
+    num: i128,
+    den: i128,
+}
+
+impl Rational {
+    /// `num / den` in lowest terms. Panics if `den` is zero.
+    pub fn new(num: i128, den: i128) -> Self {
+        assert!(den != 0, "Rational denominator must be non-zero.");
+        let divisor = gcd(num.abs(), den.abs()) * den.signum();
+        Self {
+            num: num / divisor,
+            den: den / divisor,
+        }
+    }
+
+    pub fn numer(&self) -> i128 {
+        self.num
+    }
+
+    pub fn denom(&self) -> i128 {
+        self.den
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.den == 1
+    }
+
+    pub fn to_integer(&self) -> Option<i128> {
+        self.is_integer().then_some(self.num)
+    }
+
+    pub fn floor(&self) -> i128 {
+        self.num.div_euclid(self.den)
+    }
+
+    pub fn abs(&self) -> Self {
+        Self {
+            num: self.num.abs(),
+            den: self.den,
+        }
+    }
+
+    pub fn recip(&self) -> Self {
+        Self::new(self.den, self.num)
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.num as f64 / self.den as f64
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(value: i64) -> Self {
+        Self::from(value as i128)
+    }
+}
+
+impl From<i128> for Rational {
+    fn from(value: i128) -> Self {
+        Self { num: value, den: 1 }
+    }
+}
+
+impl Zero for Rational {
+    fn zero() -> Self {
+        Self::from(0i128)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.num == 0
+    }
+}
+
+impl One for Rational {
+    fn one() -> Self {
+        Self::from(1i128)
+    }
+}
+
+impl Add for Rational {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        let divisor = gcd(self.den, rhs.den);
+        let den = self.den / divisor * rhs.den;
+        Self::new(
+            self.num * (rhs.den / divisor) + rhs.num * (self.den / divisor),
+            den,
+        )
+    }
+}
+
+impl Sub for Rational {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl Mul for Rational {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let a = gcd(self.num.abs(), rhs.den).max(1);
+        let b = gcd(rhs.num.abs(), self.den).max(1);
+        Self::new(
+            (self.num / a) * (rhs.num / b),
+            (self.den / b) * (rhs.den / a),
+        )
+    }
+}
+
+impl Div for Rational {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.recip()
+    }
+}
+
+impl Neg for Rational {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            num: -self.num,
+            den: self.den,
+        }
+    }
+}
+
+impl AddAssign for Rational {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Rational {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Sum for Rational {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |acc, value| acc + value)
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rational {
+    /// Cross-multiplies when that fits, and otherwise compares continued fraction terms, which
+    /// never overflows.
+    fn cmp(&self, other: &Self) -> Ordering {
+        if let (Some(left), Some(right)) = (
+            self.num.checked_mul(other.den),
+            other.num.checked_mul(self.den),
+        ) {
+            return left.cmp(&right);
+        }
+        let (mut a, mut b) = ((self.num, self.den), (other.num, other.den));
+        // each step compares integer parts, then flips the comparison to the reciprocals of
+        // the fractional parts
+        let mut flipped = false;
+        loop {
+            let order = a.0.div_euclid(a.1).cmp(&b.0.div_euclid(b.1));
+            let (a_rem, b_rem) = (a.0.rem_euclid(a.1), b.0.rem_euclid(b.1));
+            let order = match order {
+                Ordering::Equal if a_rem == 0 || b_rem == 0 => a_rem.cmp(&b_rem),
+                Ordering::Equal => {
+                    (a, b) = ((a.1, a_rem), (b.1, b_rem));
+                    flipped = !flipped;
+                    continue;
+                }
+                order => order,
+            };
+            return if flipped { order.reverse() } else { order };
+        }
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let half = Rational::new(2, 4);
+        let third = Rational::new(-1, -3);
+        assert_eq!((1, 2), (half.numer(), half.denom()));
+        assert_eq!(Rational::new(5, 6), half + third);
+        assert_eq!(Rational::new(1, 6), half - third);
+        assert_eq!(Rational::new(1, 6), half * third);
+        assert_eq!(Rational::new(3, 2), half / third);
+        assert_eq!(Rational::new(-1, 2), Rational::new(1, -2));
+        assert_eq!(-1, Rational::new(-1, 2).floor());
+        assert!(Rational::new(-1, 2) < third && third < half);
+        assert_eq!(Some(4), Rational::new(12, 3).to_integer());
+        assert_eq!("-7/2", Rational::new(7, -2).to_string());
+        assert_eq!(
+            Rational::one(),
+            [half, third, Rational::new(1, 6)].into_iter().sum()
+        );
+        assert_eq!(Rational::zero(), Rational::new(0, -5));
+
+        // cancelling first keeps large products in range
+        let big = Rational::new(i64::MAX as i128 * 3, 7);
+        assert_eq!(Rational::from(i64::MAX), big * Rational::new(7, 3));
+
+        // comparisons whose cross products would overflow
+        let huge = Rational::new(i128::MAX, i128::MAX - 1);
+        let larger = Rational::new(i128::MAX - 1, i128::MAX - 2);
+        assert!(Rational::one() < huge && huge < larger);
+        assert!(-larger < -huge);
+        assert_eq!(Ordering::Equal, huge.cmp(&huge));
+    }
+}
@@ -0,0 +1,317 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use num_traits::cast;
+
+use crate::structs::coord::{Coord, Coordinate};
+
+/// An axis-aligned box of integer points. Both corners are inclusive, matching puzzle input
+/// like `x=10..12,y=10..12,z=10..12`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Aabb<T: Coordinate, const N: usize> {
+    pub min: Coord<T, N>,
+    pub max: Coord<T, N>,
+}
+
+impl<T: Coordinate, const N: usize> Aabb<T, N> {
+    /// The box from `min` to `max`, or `None` if `min` exceeds `max` on any axis.
+    pub fn new(min: Coord<T, N>, max: Coord<T, N>) -> Option<Self> {
+        (0..N)
+            .all(|axis| min.0[axis] <= max.0[axis])
+            .then_some(Self { min, max })
+    }
+
+    /// The smallest box with both points as corners, in whatever order they are given.
+    pub fn from_corners(a: Coord<T, N>, b: Coord<T, N>) -> Self {
+        Self {
+            min: Coord::new(std::array::from_fn(|axis| a.0[axis].min(b.0[axis]))),
+            max: Coord::new(std::array::from_fn(|axis| a.0[axis].max(b.0[axis]))),
+        }
+    }
+
+    pub fn point(pos: Coord<T, N>) -> Self {
+        Self { min: pos, max: pos }
+    }
+
+    /// The smallest box containing every box, or `None` if there are none.
+    pub fn bounding<'a>(boxes: impl IntoIterator<Item = &'a Self>) -> Option<Self>
+    where
+        T: 'a,
+    {
+        boxes.into_iter().copied().reduce(|acc, other| Self {
+            min: Coord::new(std::array::from_fn(|axis| {
+                acc.min.0[axis].min(other.min.0[axis])
+            })),
+            max: Coord::new(std::array::from_fn(|axis| {
+                acc.max.0[axis].max(other.max.0[axis])
+            })),
+        })
+    }
+
+    /// Number of points along `axis`, saturating only for the full range of a 128-bit type.
+    pub fn side(&self, axis: usize) -> u128 {
+        span(self.min.0[axis], self.max.0[axis]).saturating_add(1)
+    }
+
+    /// Number of points in the box, saturating at `u128::MAX`.
+    pub fn volume(&self) -> u128 {
+        (0..N)
+            .map(|axis| self.side(axis))
+            .fold(1, u128::saturating_mul)
+    }
+
+    pub fn contains(&self, pos: &Coord<T, N>) -> bool {
+        (0..N).all(|axis| (self.min.0[axis]..=self.max.0[axis]).contains(&pos.0[axis]))
+    }
+
+    pub fn contains_box(&self, other: &Self) -> bool {
+        self.contains(&other.min) && self.contains(&other.max)
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        (0..N).all(|axis| {
+            self.min.0[axis] <= other.max.0[axis] && other.min.0[axis] <= self.max.0[axis]
+        })
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        Self::new(
+            Coord::new(std::array::from_fn(|axis| {
+                self.min.0[axis].max(other.min.0[axis])
+            })),
+            Coord::new(std::array::from_fn(|axis| {
+                self.max.0[axis].min(other.max.0[axis])
+            })),
+        )
+    }
+
+    /// The points of `self` outside `other`, as at most `2 * N` disjoint boxes. Slabs are cut
+    /// off one axis at a time, so earlier axes get the larger pieces.
+    pub fn subtract(&self, other: &Self) -> Vec<Self> {
+        let Some(overlap) = self.intersection(other) else {
+            return vec![*self];
+        };
+        let mut pieces = Vec::new();
+        let mut remaining = *self;
+        for axis in 0..N {
+            if remaining.min.0[axis] < overlap.min.0[axis] {
+                let mut below = remaining;
+                below.max.0[axis] = overlap.min.0[axis] - T::one();
+                pieces.push(below);
+            }
+            if overlap.max.0[axis] < remaining.max.0[axis] {
+                let mut above = remaining;
+                above.min.0[axis] = overlap.max.0[axis] + T::one();
+                pieces.push(above);
+            }
+            remaining.min.0[axis] = overlap.min.0[axis];
+            remaining.max.0[axis] = overlap.max.0[axis];
+        }
+        pieces
+    }
+
+    /// Manhattan distance from `pos` to the nearest point of the box; zero inside it.
+    pub fn distance_to(&self, pos: &Coord<T, N>) -> u128 {
+        (0..N)
+            .map(|axis| {
+                let (value, min, max) = (pos.0[axis], self.min.0[axis], self.max.0[axis]);
+                if value < min {
+                    span(value, min)
+                } else if value > max {
+                    span(max, value)
+                } else {
+                    0
+                }
+            })
+            .fold(0, u128::saturating_add)
+    }
+
+    /// Halves the box along every axis longer than one point, giving up to `2^N` disjoint
+    /// children that cover it. A single point has no children.
+    pub fn split(&self) -> Vec<Self> {
+        let mut children = vec![*self];
+        for axis in 0..N {
+            let (low, high) = (self.min.0[axis], self.max.0[axis]);
+            if low == high {
+                continue;
+            }
+            let half = cast(span(low, high) / 2).expect("half a span fits in the coordinate type");
+            let mid = low + half;
+            children = children
+                .into_iter()
+                .flat_map(|child| {
+                    let (mut lower, mut upper) = (child, child);
+                    lower.max.0[axis] = mid;
+                    upper.min.0[axis] = mid + T::one();
+                    [lower, upper]
+                })
+                .collect();
+        }
+        if children.len() == 1 {
+            children.clear();
+        }
+        children
+    }
+
+    /// Branch and bound search for the point of `self` scoring highest, where `bound(region)`
+    /// must never be less than the score of any point inside `region` and must be exact for
+    /// single points. Ties go to the point nearest the origin. Any shape can be searched this
+    /// way, such as the octahedra of y18d23, by counting the shapes each region touches.
+    pub fn search_max<F>(&self, bound: F) -> (Coord<T, N>, usize)
+    where
+        F: Fn(&Self) -> usize,
+    {
+        let origin = Coord::origin();
+        let key = |region: &Self| {
+            (
+                bound(region),
+                Reverse(region.distance_to(&origin)),
+                Reverse(region.volume()),
+            )
+        };
+        let mut queue = BinaryHeap::from([(key(self), *self)]);
+        while let Some(((score, _, _), region)) = queue.pop() {
+            if region.min == region.max {
+                return (region.min, score);
+            }
+            queue.extend(region.split().into_iter().map(|child| (key(&child), child)));
+        }
+        unreachable!("the queue only empties after popping a single point")
+    }
+
+    /// The point covered by the most boxes, nearest the origin on ties, and how many boxes
+    /// cover it. `None` if there are no boxes.
+    pub fn max_overlap(boxes: &[Self]) -> Option<(Coord<T, N>, usize)> {
+        let bounds = Self::bounding(boxes)?;
+        Some(bounds.search_max(|region| boxes.iter().filter(|b| b.intersects(region)).count()))
+    }
+
+    /// Total number of points covered by at least one box, or `None` if counting them
+    /// overflows as in `signed_volume`.
+    pub fn union_volume<'a>(boxes: impl IntoIterator<Item = &'a Self>) -> Option<u128>
+    where
+        T: 'a,
+    {
+        Self::signed_volume(boxes.into_iter().map(|&b| (b, true))).map(|volume| volume as u128)
+    }
+
+    /// Points left on after switching each box on or off in turn, as in y21d22. Each step adds
+    /// signed corrections for its overlaps with earlier pieces, so no point is counted twice.
+    /// `None` if a piece's volume or the running total does not fit in an `i128`.
+    pub fn signed_volume(steps: impl IntoIterator<Item = (Self, bool)>) -> Option<i128> {
+        let mut pieces: Vec<(Self, i128)> = Vec::new();
+        for (step, on) in steps {
+            let corrections: Vec<(Self, i128)> = pieces
+                .iter()
+                .filter_map(|&(piece, sign)| Some((piece.intersection(&step)?, -sign)))
+                .collect();
+            pieces.extend(corrections);
+            if on {
+                pieces.push((step, 1));
+            }
+        }
+        pieces.iter().try_fold(0i128, |total, (piece, sign)| {
+            let volume = i128::try_from(piece.volume()).ok()?;
+            total.checked_add(sign * volume)
+        })
+    }
+}
+
+/// `high - low` for `low <= high`, exact for every primitive integer type.
+fn span<T: Coordinate>(low: T, high: T) -> u128 {
+    match (low.to_i128(), high.to_i128()) {
+        (Some(low), Some(high)) => high.wrapping_sub(low) as u128,
+        _ => high.to_u128().expect("unsigned") - low.to_u128().expect("unsigned"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::coord::{Coord2, Coord3};
+
+    fn cuboid(x: (i64, i64), y: (i64, i64), z: (i64, i64)) -> Aabb<i64, 3> {
+        Aabb::new(Coord3::new3d(x.0, y.0, z.0), Coord3::new3d(x.1, y.1, z.1)).unwrap()
+    }
+
+    #[test]
+    fn fabric_claims() {
+        // y18d03 example
+        let claim = |x, y, w, h| {
+            Aabb::new(Coord2::new2d(x, y), Coord2::new2d(x + w - 1, y + h - 1)).unwrap()
+        };
+        let claims = [claim(1, 3, 4, 4), claim(3, 1, 4, 4), claim(5, 5, 2, 2)];
+        assert_eq!(4, claims[0].intersection(&claims[1]).unwrap().volume());
+        assert!(!claims[2].intersects(&claims[0]) && !claims[2].intersects(&claims[1]));
+        assert_eq!(Some(32), Aabb::union_volume(&claims));
+        let (pos, count) = Aabb::max_overlap(&claims).unwrap();
+        assert_eq!((Coord2::new2d(3, 3), 2), (pos, count));
+        assert_eq!(None, Aabb::new(Coord2::new2d(1, 1), Coord2::new2d(0, 2)));
+
+        // sides and distances stay exact at the limits of the coordinate type
+        let wide = Aabb::new(Coord2::new2d(i64::MIN, 0), Coord2::new2d(i64::MAX, 0)).unwrap();
+        assert_eq!(1 << 64, wide.side(0));
+        let far = Aabb::point(Coord::new([u128::MAX]));
+        assert_eq!(u128::MAX, far.distance_to(&Coord::new([0])));
+        assert_eq!(1, far.side(0));
+        let halves = wide.split();
+        assert_eq!(Coord2::new2d(-1, 0), halves[0].max);
+        assert_eq!(Coord2::new2d(0, 0), halves[1].min);
+        let (pos, score) = wide.search_max(|region| region.contains(&Coord2::new2d(7, 0)) as usize);
+        assert_eq!((Coord2::new2d(7, 0), 1), (pos, score));
+        let plane = Aabb::from_corners(
+            Coord2::new2d(i64::MIN, i64::MIN),
+            Coord2::new2d(i64::MAX, i64::MAX),
+        );
+        assert_eq!(u128::MAX, plane.volume());
+        assert_eq!(None, Aabb::union_volume(&[plane]));
+        assert_eq!(Some((Coord2::origin(), 1)), Aabb::max_overlap(&[plane]));
+    }
+
+    #[test]
+    fn subtraction() {
+        let cube = cuboid((-1, 1), (-1, 1), (-1, 1));
+        let pieces = cube.subtract(&Aabb::point(Coord3::origin()));
+        assert_eq!(6, pieces.len());
+        assert_eq!(26, pieces.iter().map(|p| p.volume()).sum::<u128>());
+        for (i, a) in pieces.iter().enumerate() {
+            assert!(cube.contains_box(a) && !a.contains(&Coord3::origin()));
+            assert!(pieces[i + 1..].iter().all(|b| !a.intersects(b)));
+        }
+        assert_eq!(vec![cube], cube.subtract(&cuboid((5, 6), (0, 0), (0, 0))));
+        assert!(cube.subtract(&cuboid((-2, 2), (-2, 2), (-2, 2))).is_empty());
+    }
+
+    #[test]
+    fn reactor_reboot() {
+        // y21d22 small example
+        let steps = [
+            (cuboid((10, 12), (10, 12), (10, 12)), true),
+            (cuboid((11, 13), (11, 13), (11, 13)), true),
+            (cuboid((9, 11), (9, 11), (9, 11)), false),
+            (cuboid((10, 10), (10, 10), (10, 10)), true),
+        ];
+        assert_eq!(Some(39), Aabb::signed_volume(steps));
+    }
+
+    #[test]
+    fn nanobots() {
+        // y18d23 part two example: search for the point in range of the most octahedra
+        let bots = [
+            (Coord3::new3d(10, 12, 12), 2),
+            (Coord3::new3d(12, 14, 12), 2),
+            (Coord3::new3d(16, 12, 12), 4),
+            (Coord3::new3d(14, 14, 14), 6),
+            (Coord3::new3d(50, 50, 50), 200),
+            (Coord3::new3d(10, 10, 10), 5),
+        ];
+        let bounds = Aabb::from_corners(Coord3::new3d(0, 0, 0), Coord3::new3d(64, 64, 64));
+        let (pos, count) = bounds.search_max(|region| {
+            bots.iter()
+                .filter(|(bot, radius)| region.distance_to(bot) <= *radius)
+                .count()
+        });
+        assert_eq!((Coord3::new3d(12, 12, 12), 5), (pos, count));
+        assert_eq!(36, pos.manhattan_distance(Coord3::origin()));
+    }
+}
@@ -0,0 +1,374 @@
+use advent_ocr::Scannable;
+use std::fmt::{Display, Formatter};
+use std::ops::{BitAnd, BitOr, BitXor, Not};
+
+use crate::structs::coord::Coord2U;
+use crate::structs::grid::Grid;
+
+/// A 2-D grid of booleans packed 64 to a word. Each row starts on a fresh word, with cell `x`
+/// in bit `x % 64` of word `x / 64`, so whole rows can be combined with word operations.
+/// Bits past the width are always zero.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    words_per_row: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        let words_per_row = width.div_ceil(64);
+        Self {
+            width,
+            height,
+            words_per_row,
+            words: vec![0; words_per_row * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, pos: Coord2U) -> Option<bool> {
+        let (word, bit) = self.locate(pos)?;
+        Some(self.words[word] >> bit & 1 == 1)
+    }
+
+    /// Sets a cell, returning its previous value, or `None` if it is outside the grid.
+    pub fn set(&mut self, pos: Coord2U, on: bool) -> Option<bool> {
+        let (word, bit) = self.locate(pos)?;
+        let previous = self.words[word] >> bit & 1 == 1;
+        if on {
+            self.words[word] |= 1 << bit;
+        } else {
+            self.words[word] &= !(1 << bit);
+        }
+        Some(previous)
+    }
+
+    pub fn toggle(&mut self, pos: Coord2U) -> Option<bool> {
+        let on = !self.get(pos)?;
+        self.set(pos, on);
+        Some(on)
+    }
+
+    pub fn row(&self, y: usize) -> &[u64] {
+        &self.words[y * self.words_per_row..(y + 1) * self.words_per_row]
+    }
+
+    /// Mutable access to a row's words. Bits past the width must be left clear.
+    pub fn row_mut(&mut self, y: usize) -> &mut [u64] {
+        &mut self.words[y * self.words_per_row..(y + 1) * self.words_per_row]
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn count_row(&self, y: usize) -> usize {
+        self.row(y)
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    /// Positions of every set cell in row-major order.
+    pub fn ones(&self) -> impl Iterator<Item = Coord2U> + '_ {
+        self.words
+            .iter()
+            .enumerate()
+            .flat_map(move |(index, &word)| {
+                let y = index / self.words_per_row;
+                let base = index % self.words_per_row * 64;
+                let mut remaining = word;
+                std::iter::from_fn(move || {
+                    if remaining == 0 {
+                        return None;
+                    }
+                    let bit = remaining.trailing_zeros() as usize;
+                    remaining &= remaining - 1;
+                    Some(Coord2U::new2d(base + bit, y))
+                })
+            })
+    }
+
+    /// A copy with every cell moved by `(dx, dy)`. Cells pushed off the edge are lost and the
+    /// vacated cells are clear.
+    pub fn shifted(&self, dx: i64, dy: i64) -> Self {
+        let mut shifted = Self::new(self.width, self.height);
+        for y in 0..self.height {
+            let source = y as i64 - dy;
+            if !(0..self.height as i64).contains(&source) {
+                continue;
+            }
+            let row = self.row(source as usize);
+            let target = shifted.row_mut(y);
+            if dx >= 0 {
+                shift_up(row, target, dx as usize);
+            } else {
+                shift_down(row, target, dx.unsigned_abs() as usize);
+            }
+            shifted.clear_padding(y);
+        }
+        shifted
+    }
+
+    /// Bit planes of how many of each cell's eight neighbors are set, least significant first.
+    /// Cell counts are read by combining the same bit from each plane.
+    pub fn moore_counts(&self) -> [BitGrid; 4] {
+        let mut planes: [BitGrid; 4] = std::array::from_fn(|_| Self::new(self.width, self.height));
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                if dx == 0 && dy == 0 {
+                    continue;
+                }
+                // ripple-carry add the shifted grid into the planes, one word at a time
+                let mut carry = self.shifted(dx, dy).words;
+                for plane in planes.iter_mut() {
+                    for (word, carried) in plane.words.iter_mut().zip(carry.iter_mut()) {
+                        let sum = *word ^ *carried;
+                        *carried &= *word;
+                        *word = sum;
+                    }
+                }
+            }
+        }
+        planes
+    }
+
+    /// Cells whose neighbor count, as produced by `moore_counts`, is exactly `count`. No cell
+    /// has more than eight neighbors, so larger counts match nothing.
+    pub fn count_equals(planes: &[BitGrid; 4], count: usize) -> BitGrid {
+        let empty = Self::new(planes[0].width, planes[0].height);
+        if count > 8 {
+            return empty;
+        }
+        let mut result = !&empty;
+        for (bit, plane) in planes.iter().enumerate() {
+            result = if count >> bit & 1 == 1 {
+                &result & plane
+            } else {
+                &result & &!plane
+            };
+        }
+        result
+    }
+
+    /// One generation of a life-like automaton: clear cells with a neighbor count in `birth`
+    /// turn on, set cells with a count in `survive` stay on, and everything else turns off.
+    pub fn life_step(&self, birth: &[usize], survive: &[usize]) -> BitGrid {
+        let planes = self.moore_counts();
+        let any = |counts: &[usize]| {
+            counts
+                .iter()
+                .fold(Self::new(self.width, self.height), |acc, &count| {
+                    &acc | &Self::count_equals(&planes, count)
+                })
+        };
+        &(&any(birth) & &!self) | &(&any(survive) & self)
+    }
+
+    fn locate(&self, pos: Coord2U) -> Option<(usize, usize)> {
+        let (x, y) = pos.destructured();
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        Some((y * self.words_per_row + x / 64, x % 64))
+    }
+
+    fn clear_padding(&mut self, y: usize) {
+        let used = self.width % 64;
+        if used != 0 {
+            let last = (y + 1) * self.words_per_row - 1;
+            self.words[last] &= (1 << used) - 1;
+        }
+    }
+}
+
+/// Moves every bit of `row` `n` places towards the most significant end.
+fn shift_up(row: &[u64], target: &mut [u64], n: usize) {
+    let (words, bits) = (n / 64, n % 64);
+    for i in (words..row.len()).rev() {
+        let mut word = row[i - words] << bits;
+        if bits > 0 && i > words {
+            word |= row[i - words - 1] >> (64 - bits);
+        }
+        target[i] = word;
+    }
+}
+
+/// Moves every bit of `row` `n` places towards the least significant end.
+fn shift_down(row: &[u64], target: &mut [u64], n: usize) {
+    let (words, bits) = (n / 64, n % 64);
+    for i in 0..row.len().saturating_sub(words) {
+        let mut word = row[i + words] >> bits;
+        if bits > 0 && i + words + 1 < row.len() {
+            word |= row[i + words + 1] << (64 - bits);
+        }
+        target[i] = word;
+    }
+}
+
+macro_rules! impl_bit_op {
+    ($trait:ident, $method:ident, $op:tt) => {
+        impl $trait for &BitGrid {
+            type Output = BitGrid;
+
+            fn $method(self, rhs: Self) -> BitGrid {
+                assert_eq!(
+                    (self.width, self.height),
+                    (rhs.width, rhs.height),
+                    "Bit grids must have the same dimensions."
+                );
+                let mut result = self.clone();
+                for (word, other) in result.words.iter_mut().zip(rhs.words.iter()) {
+                    *word $op *other;
+                }
+                result
+            }
+        }
+    };
+}
+
+impl_bit_op!(BitAnd, bitand, &=);
+impl_bit_op!(BitOr, bitor, |=);
+impl_bit_op!(BitXor, bitxor, ^=);
+
+impl Not for &BitGrid {
+    type Output = BitGrid;
+
+    fn not(self) -> BitGrid {
+        let mut result = self.clone();
+        for word in result.words.iter_mut() {
+            *word = !*word;
+        }
+        for y in 0..result.height {
+            result.clear_padding(y);
+        }
+        result
+    }
+}
+
+impl From<&Grid<bool, 2>> for BitGrid {
+    fn from(grid: &Grid<bool, 2>) -> Self {
+        let mut bits = Self::new(grid.width(), grid.height());
+        for (pos, &on) in grid.iter_with_coords() {
+            if on {
+                bits.set(pos, true);
+            }
+        }
+        bits
+    }
+}
+
+impl From<&BitGrid> for Grid<bool, 2> {
+    fn from(bits: &BitGrid) -> Self {
+        Grid::new2d_with_fn(bits.width, bits.height, |index| {
+            bits.get(Coord2U::new2d(index % bits.width, index / bits.width))
+                .expect("index is inside the grid")
+        })
+    }
+}
+
+impl Display for BitGrid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut display = String::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let on = self
+                    .get(Coord2U::new2d(x, y))
+                    .expect("position is inside the grid");
+                display.push(if on { '#' } else { '.' });
+            }
+            display.push('\n');
+        }
+        display.pop();
+        write!(f, "{}", display)
+    }
+}
+
+impl Scannable for &BitGrid {
+    fn normalize(&self) -> String {
+        let mut image = self.to_string();
+        image.push('\n');
+        image
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lights() -> Grid<bool, 2> {
+        Grid::new2d_map_str(".#.#.#\n...##.\n#....#\n..#...\n#.#..#\n####..", |c| {
+            c == '#'
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn get_set_and_count() {
+        let mut bits = BitGrid::new(130, 3);
+        assert_eq!(Some(false), bits.set(Coord2U::new2d(129, 2), true));
+        assert_eq!(Some(true), bits.get(Coord2U::new2d(129, 2)));
+        assert_eq!(None, bits.set(Coord2U::new2d(130, 0), true));
+        bits.toggle(Coord2U::new2d(64, 0));
+        assert_eq!(2, bits.count_ones());
+        assert_eq!(1, bits.count_row(2));
+        assert_eq!(
+            vec![Coord2U::new2d(64, 0), Coord2U::new2d(129, 2)],
+            bits.ones().collect::<Vec<_>>()
+        );
+        // complements stay inside the width
+        assert_eq!(130 * 3 - 2, (!&bits).count_ones());
+    }
+
+    #[test]
+    fn shifting_across_words() {
+        let mut bits = BitGrid::new(100, 2);
+        bits.set(Coord2U::new2d(63, 0), true);
+        bits.set(Coord2U::new2d(99, 1), true);
+        let east = bits.shifted(1, 0);
+        assert_eq!(vec![Coord2U::new2d(64, 0)], east.ones().collect::<Vec<_>>());
+        let north_west = bits.shifted(-70, -1);
+        assert_eq!(
+            vec![Coord2U::new2d(29, 0)],
+            north_west.ones().collect::<Vec<_>>()
+        );
+        assert!(bits.shifted(0, 2).is_empty());
+    }
+
+    #[test]
+    fn round_trip_and_life() {
+        let grid = lights();
+        let mut bits = BitGrid::from(&grid);
+        assert_eq!(grid, Grid::from(&bits));
+        assert_eq!(format!("{grid}"), bits.to_string());
+        assert_eq!((&grid).normalize(), (&bits).normalize());
+
+        let planes = bits.moore_counts();
+        assert!(
+            BitGrid::count_equals(&planes, 3)
+                .get(Coord2U::new2d(2, 1))
+                .unwrap()
+        );
+        assert!(BitGrid::count_equals(&planes, 16).is_empty());
+        for _ in 0..4 {
+            bits = bits.life_step(&[3], &[2, 3]);
+        }
+        assert_eq!(4, bits.count_ones());
+    }
+}
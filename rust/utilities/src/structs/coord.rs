@@ -110,39 +110,10 @@ impl<T: Coordinate, const N: usize> Coord<T, N> {
         Self([T::zero(); N])
     }
 
-    pub fn get_neighbors(&self) -> impl Iterator<Item = Coord<T, N>> + '_ + Debug {
-        let mut neighbors: Vec<Self> = Vec::with_capacity(3.pow(N as u32) as usize);
-
-        // seed, need to remove later
-        neighbors.push(self.clone());
-
-        // expand
-        for dim in 0..N {
-            let left: Vec<_> = neighbors
-                .iter()
-                .map(|pos| {
-                    let mut pos = pos.clone();
-                    pos.0[dim] = pos.0[dim] - T::one();
-                    pos
-                })
-                .collect();
-            let right: Vec<_> = neighbors
-                .iter()
-                .map(|pos| {
-                    let mut pos = pos.clone();
-                    pos.0[dim] = pos.0[dim] + T::one();
-                    pos
-                })
-                .collect();
-            neighbors.extend(left.into_iter());
-            neighbors.extend(right.into_iter());
-        }
-
-        // remove seed
-        neighbors
-            .into_iter()
-            .dropping(1)
-            .sorted_unstable_by(|a, b| a.partial_cmp(b).unwrap())
+    /// The same positions as `moore_neighbors`, which it predates. Both exclude this position
+    /// and come in sorted order.
+    pub fn get_neighbors(&self) -> impl Iterator<Item = Coord<T, N>> + use<T, N> {
+        self.moore_neighbors()
     }
 
     pub fn min_max<'a>(coords: impl IntoIterator<Item = &'a Self>) -> (Self, Self)
@@ -164,6 +135,96 @@ impl<T: Coordinate, const N: usize> Coord<T, N> {
         }
         (Coord::new(min), Coord::new(max))
     }
+
+    /// Whether every coordinate lies in `0..dimension` for its axis.
+    pub fn in_bounds(&self, dimensions: &[usize; N]) -> bool {
+        self.0
+            .iter()
+            .zip(dimensions.iter())
+            .all(|(&n, &dimension)| n.to_usize().is_some_and(|n| n < dimension))
+    }
+
+    /// Positions at the given offsets from this one, skipping any that cannot be represented in
+    /// `T`, such as negative positions for unsigned coordinates.
+    pub fn offset_by<'a, I>(&self, offsets: I) -> impl Iterator<Item = Self> + use<'a, T, N, I>
+    where
+        I: IntoIterator<Item = &'a Coord<i64, N>>,
+    {
+        let origin = *self;
+        offsets
+            .into_iter()
+            .filter_map(move |offset| origin.checked_offset(offset))
+    }
+
+    fn checked_offset(&self, offset: &Coord<i64, N>) -> Option<Self> {
+        let mut moved = *self;
+        for (n, &step) in moved.0.iter_mut().zip(offset.0.iter()) {
+            *n = T::from(n.to_i64()?.checked_add(step)?)?;
+        }
+        Some(moved)
+    }
+
+    /// The 2N positions sharing a face with this one.
+    pub fn face_neighbors(&self) -> impl Iterator<Item = Self> + use<T, N> {
+        self.neighbors_within(1, false)
+    }
+
+    /// The 3^N - 1 positions touching this one, diagonals included, in sorted order. Positions
+    /// that `T` cannot represent are skipped.
+    pub fn moore_neighbors(&self) -> impl Iterator<Item = Self> + use<T, N> {
+        self.neighbors_within(1, true)
+    }
+
+    /// Positions within `radius`, measured by Chebyshev distance if `diagonals` is set and by
+    /// Manhattan distance otherwise.
+    pub fn neighbors_within(
+        &self,
+        radius: usize,
+        diagonals: bool,
+    ) -> impl Iterator<Item = Self> + use<T, N> {
+        let origin = *self;
+        Coord::<i64, N>::offset_iter(radius, diagonals)
+            .filter_map(move |offset| origin.checked_offset(&offset))
+    }
+
+    /// `neighbors_within`, limited to positions inside a grid of the given dimensions.
+    pub fn neighbors_within_bounds(
+        &self,
+        radius: usize,
+        diagonals: bool,
+        dimensions: &[usize; N],
+    ) -> impl Iterator<Item = Self> + use<T, N> {
+        let dimensions = *dimensions;
+        self.neighbors_within(radius, diagonals)
+            .filter(move |pos| pos.in_bounds(&dimensions))
+    }
+}
+
+impl<const N: usize> Coord<i64, N> {
+    /// Every nonzero offset within `radius`, by Chebyshev distance if `diagonals` is set and by
+    /// Manhattan distance otherwise. Offsets are ordered with the first axis changing fastest,
+    /// like grid indices.
+    pub fn offsets(radius: usize, diagonals: bool) -> Vec<Self> {
+        Self::offset_iter(radius, diagonals).collect()
+    }
+
+    /// `offsets`, generated lazily without allocating.
+    pub fn offset_iter(radius: usize, diagonals: bool) -> impl Iterator<Item = Self> {
+        let radius = radius as i64;
+        let side = (2 * radius + 1) as usize;
+        (0..side.pow(N as u32))
+            .map(move |mut index| {
+                Coord(std::array::from_fn(|_| {
+                    let n = (index % side) as i64 - radius;
+                    index /= side;
+                    n
+                }))
+            })
+            .filter(|offset| *offset != Self::origin())
+            .filter(move |offset| {
+                diagonals || offset.0.iter().map(|n| n.abs()).sum::<i64>() <= radius
+            })
+    }
 }
 
 impl<T: Coordinate, const N: usize> Add for Coord<T, N> {
@@ -702,3 +763,35 @@ fn signed_math_operations() {
     assert_eq!(32, pos1.manhattan_distance(pos2));
     assert_eq!(80, Coord::<i64, 4>::origin().get_neighbors().count());
 }
+
+#[test]
+fn n_dimensional_neighbors() {
+    assert_eq!(6, Coord::<i64, 3>::origin().face_neighbors().count());
+    assert_eq!(80, Coord::<i64, 4>::origin().moore_neighbors().count());
+    assert_eq!(
+        Coord::<i64, 2>::all_adjacent().to_vec(),
+        Coord::<i64, 2>::offsets(1, true)
+    );
+    assert_eq!(24, Coord::<i64, 2>::offsets(2, true).len());
+    assert_eq!(12, Coord::<i64, 2>::offsets(2, false).len());
+    assert_eq!(24, Coord::<i64, 3>::offsets(2, false).len());
+
+    // unsigned coordinates skip positions below zero
+    let corner = Coord::new3d(0usize, 0, 0);
+    assert_eq!(3, corner.face_neighbors().count());
+    assert_eq!(7, corner.moore_neighbors().count());
+
+    let dimensions = [3, 3, 2];
+    let edge = Coord::new3d(2usize, 1, 1);
+    let bounded: Vec<_> = edge.neighbors_within_bounds(1, false, &dimensions).collect();
+    assert_eq!(
+        vec![
+            Coord::new3d(2, 1, 0),
+            Coord::new3d(2, 0, 1),
+            Coord::new3d(1, 1, 1),
+            Coord::new3d(2, 2, 1),
+        ],
+        bounded
+    );
+    assert!(!Coord::new3d(0i64, -1, 0).in_bounds(&dimensions));
+}
@@ -5,8 +5,14 @@ pub mod grid_errors;
 pub mod grid_index;
 pub mod grid_iterators;
 pub mod grid_methods;
+pub mod grid_orientation;
+pub mod grid_pattern;
+pub mod grid_ray;
+pub mod grid_regions;
 pub mod grid_rotation;
 mod grid_scannable;
+pub mod grid_tiling;
+pub mod grid_wrap;
 
 use std::ops::Deref;
 
@@ -19,10 +25,16 @@ pub use grid_index::*;
 pub use grid_iterators::*;
 #[allow(unused_imports)]
 pub use grid_methods::*;
+pub use grid_orientation::*;
+pub use grid_pattern::*;
+pub use grid_ray::*;
+pub use grid_regions::*;
 #[allow(unused_imports)]
 pub use grid_rotation::*;
 #[allow(unused_imports)]
 pub use grid_scannable::*;
+pub use grid_tiling::*;
+pub use grid_wrap::*;
 
 pub type Grid2<T> = Grid<T, 2>;
 
@@ -0,0 +1,265 @@
+use std::ops::Index;
+
+use crate::structs::coord::Coord2U;
+
+use super::{Grid, GridRotation};
+
+/// One of the eight symmetries of a rectangle: an optional mirror across the vertical axis,
+/// followed by some number of clockwise quarter turns.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct D4 {
+    pub quarter_turns: u8,
+    pub flipped: bool,
+}
+
+impl D4 {
+    pub const IDENTITY: Self = Self::new(0, false);
+
+    pub const fn new(quarter_turns: u8, flipped: bool) -> Self {
+        Self {
+            quarter_turns: quarter_turns % 4,
+            flipped,
+        }
+    }
+
+    /// All eight orientations, the four rotations first.
+    pub fn entries() -> [Self; 8] {
+        std::array::from_fn(|i| Self::new(i as u8 % 4, i >= 4))
+    }
+
+    /// The orientation reached by applying `self` and then `other`.
+    pub fn then(self, other: Self) -> Self {
+        let turns = if other.flipped {
+            other.quarter_turns + 4 - self.quarter_turns
+        } else {
+            other.quarter_turns + self.quarter_turns
+        };
+        Self::new(turns, self.flipped ^ other.flipped)
+    }
+
+    pub fn inverse(self) -> Self {
+        if self.flipped {
+            self
+        } else {
+            Self::new(4 - self.quarter_turns, false)
+        }
+    }
+
+    /// Width and height of a `dimensions` grid after reorienting it.
+    pub fn dimensions(&self, dimensions: [usize; 2]) -> [usize; 2] {
+        match self.quarter_turns % 2 {
+            0 => dimensions,
+            _ => [dimensions[1], dimensions[0]],
+        }
+    }
+
+    /// Where `pos` in a `dimensions` grid ends up once the grid is reoriented.
+    pub fn transform(&self, pos: Coord2U, dimensions: [usize; 2]) -> Coord2U {
+        let [mut width, mut height] = dimensions;
+        let (mut x, mut y) = pos.destructured();
+        if self.flipped {
+            x = width - 1 - x;
+        }
+        for _ in 0..self.quarter_turns {
+            (x, y) = (height - 1 - y, x);
+            (width, height) = (height, width);
+        }
+        Coord2U::new2d(x, y)
+    }
+}
+
+impl From<GridRotation> for D4 {
+    fn from(rotation: GridRotation) -> Self {
+        match rotation {
+            GridRotation::Left => Self::new(3, false),
+            GridRotation::Right => Self::new(1, false),
+            GridRotation::OneEighty => Self::new(2, false),
+            GridRotation::FlipX => Self::new(0, true),
+            GridRotation::FlipY => Self::new(2, true),
+        }
+    }
+}
+
+/// A reoriented view of a grid that reads through to the original cells.
+#[derive(Debug)]
+pub struct OrientedGrid<'a, T> {
+    pub grid: &'a Grid<T, 2>,
+    pub orientation: D4,
+    inverse: D4,
+    dimensions: [usize; 2],
+}
+
+impl<T> Clone for OrientedGrid<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for OrientedGrid<'_, T> {}
+
+impl<'a, T> OrientedGrid<'a, T> {
+    pub fn width(&self) -> usize {
+        self.dimensions[0]
+    }
+
+    pub fn height(&self) -> usize {
+        self.dimensions[1]
+    }
+
+    pub fn dimensions(&self) -> [usize; 2] {
+        self.dimensions
+    }
+
+    /// Position in the underlying grid of `pos` in this view.
+    pub fn source(&self, pos: Coord2U) -> Option<Coord2U> {
+        if pos.x() >= self.width() || pos.y() >= self.height() {
+            return None;
+        }
+        Some(self.inverse.transform(pos, self.dimensions))
+    }
+
+    pub fn get(&self, pos: Coord2U) -> Option<&'a T> {
+        let source = self.source(pos)?;
+        Some(&self.grid[source])
+    }
+
+    /// Cells in row-major order of the view.
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> + use<'a, T> {
+        let view = *self;
+        (0..view.height()).flat_map(move |y| {
+            (0..view.width()).map(move |x| {
+                view.get(Coord2U::new2d(x, y))
+                    .expect("position is inside the view")
+            })
+        })
+    }
+
+    pub fn to_grid(&self) -> Grid<T, 2>
+    where
+        T: Clone,
+    {
+        Grid {
+            data: self.iter().cloned().collect(),
+            dimensions: self.dimensions,
+        }
+    }
+}
+
+impl<T> Index<Coord2U> for OrientedGrid<'_, T> {
+    type Output = T;
+
+    fn index(&self, index: Coord2U) -> &Self::Output {
+        self.get(index).expect("position is outside the view")
+    }
+}
+
+impl<T> Grid<T, 2> {
+    pub fn oriented(&self, orientation: D4) -> OrientedGrid<'_, T> {
+        OrientedGrid {
+            grid: self,
+            orientation,
+            inverse: orientation.inverse(),
+            dimensions: orientation.dimensions(self.dimensions),
+        }
+    }
+
+    /// Views of the grid in all eight orientations.
+    pub fn orientations(&self) -> impl Iterator<Item = (D4, OrientedGrid<'_, T>)> {
+        D4::entries()
+            .into_iter()
+            .map(|orientation| (orientation, self.oriented(orientation)))
+    }
+}
+
+impl<T: Clone> Grid<T, 2> {
+    pub fn orient(&self, orientation: D4) -> Grid<T, 2> {
+        self.oriented(orientation).to_grid()
+    }
+}
+
+impl<T: Clone + Ord> Grid<T, 2> {
+    /// The smallest of the grid's eight orientations, comparing dimensions then cells in
+    /// row-major order, along with the orientation that produces it. Grids that are rotations
+    /// or reflections of each other share a canonical form.
+    pub fn canonical(&self) -> (Grid<T, 2>, D4) {
+        let (orientation, _) = self
+            .orientations()
+            .min_by(|(_, a), (_, b)| {
+                a.dimensions()
+                    .cmp(&b.dimensions())
+                    .then_with(|| a.iter().cmp(b.iter()))
+            })
+            .expect("there are always eight orientations");
+        (self.orient(orientation), orientation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::D4;
+    use crate::structs::{
+        coord::Coord2U,
+        grid::{Grid, GridIterator, GridRotation},
+    };
+
+    fn num_pad() -> Grid<usize, 2> {
+        (1..=8).try_collect_grid(4).unwrap()
+    }
+
+    #[test]
+    fn matches_rotate() {
+        let np = num_pad();
+        for rotation in [
+            GridRotation::Left,
+            GridRotation::Right,
+            GridRotation::OneEighty,
+            GridRotation::FlipX,
+            GridRotation::FlipY,
+        ] {
+            let copied = np.rotate(rotation);
+            assert_eq!(copied, np.orient(D4::from(rotation)));
+        }
+    }
+
+    #[test]
+    fn group_laws() {
+        let np = num_pad();
+        for a in D4::entries() {
+            assert_eq!(D4::IDENTITY, a.then(a.inverse()));
+            assert_eq!(np, np.orient(a).orient(a.inverse()));
+            for b in D4::entries() {
+                assert_eq!(np.orient(a).orient(b), np.orient(a.then(b)));
+            }
+        }
+        let distinct: std::collections::HashSet<_> =
+            D4::entries().iter().map(|&o| np.orient(o)).collect();
+        assert_eq!(8, distinct.len());
+    }
+
+    #[test]
+    fn transform_and_view() {
+        let np = num_pad();
+        let orientation = D4::new(1, true);
+        let view = np.oriented(orientation);
+        assert_eq!([2, 4], view.dimensions());
+        for (pos, value) in np.iter_with_coords() {
+            let moved = orientation.transform(pos, np.dimensions);
+            assert_eq!(value, &view[moved]);
+            assert_eq!(Some(pos), view.source(moved));
+        }
+        assert_eq!(None, view.get(Coord2U::new2d(2, 0)));
+    }
+
+    #[test]
+    fn canonical() {
+        let np = num_pad();
+        let (canonical, _) = np.canonical();
+        for orientation in D4::entries() {
+            let (other, found) = np.orient(orientation).canonical();
+            assert_eq!(canonical, other);
+            assert_eq!(canonical, np.orient(orientation).orient(found));
+        }
+        // the transpose is smallest, as narrower grids sort first
+        assert_eq!(vec![1, 5, 2, 6, 3, 7, 4, 8], canonical.data);
+    }
+}
@@ -0,0 +1,187 @@
+use crate::structs::coord::Coord2U;
+
+use super::{D4, Grid, OrientedGrid};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PatternMatch {
+    /// Position of the template's top-left cell.
+    pub pos: Coord2U,
+    /// Orientation the template was in when it matched.
+    pub orientation: D4,
+}
+
+impl<T: PartialEq> Grid<T, 2> {
+    /// Every position where `template` matches, possibly overlapping. Template cells equal to
+    /// `wildcard` match anything.
+    pub fn find_pattern(&self, template: &Grid<T, 2>, wildcard: Option<&T>) -> Vec<Coord2U> {
+        self.find_view(&template.oriented(D4::IDENTITY), wildcard)
+    }
+
+    /// Like `find_pattern`, but tries every orientation of the template. Orientations that
+    /// look the same are only tried once, so symmetric templates do not match twice.
+    pub fn find_pattern_oriented(
+        &self,
+        template: &Grid<T, 2>,
+        wildcard: Option<&T>,
+    ) -> Vec<PatternMatch> {
+        let mut views: Vec<OrientedGrid<T>> = Vec::new();
+        for (_, view) in template.orientations() {
+            let seen = views.iter().any(|other| {
+                other.dimensions() == view.dimensions() && other.iter().eq(view.iter())
+            });
+            if !seen {
+                views.push(view);
+            }
+        }
+        views
+            .into_iter()
+            .flat_map(|view| {
+                self.find_view(&view, wildcard)
+                    .into_iter()
+                    .map(move |pos| PatternMatch {
+                        pos,
+                        orientation: view.orientation,
+                    })
+            })
+            .collect()
+    }
+
+    pub fn count_pattern(
+        &self,
+        template: &Grid<T, 2>,
+        wildcard: Option<&T>,
+        oriented: bool,
+    ) -> usize {
+        match oriented {
+            true => self.find_pattern_oriented(template, wildcard).len(),
+            false => self.find_pattern(template, wildcard).len(),
+        }
+    }
+
+    /// Indices of the grid cells covered by the non-wildcard cells of each match, without
+    /// duplicates and in ascending order.
+    pub fn matched_cells(
+        &self,
+        template: &Grid<T, 2>,
+        wildcard: Option<&T>,
+        matches: &[PatternMatch],
+    ) -> Vec<usize> {
+        let mut cells: Vec<usize> = matches
+            .iter()
+            .flat_map(|found| {
+                template
+                    .iter_with_coords()
+                    .filter(move |&(_, value)| Some(value) != wildcard)
+                    .map(move |(pos, _)| {
+                        let pos = found.pos + found.orientation.transform(pos, template.dimensions);
+                        pos.get_index(&self.dimensions)
+                            .expect("matches lie inside the grid")
+                    })
+            })
+            .collect();
+        cells.sort_unstable();
+        cells.dedup();
+        cells
+    }
+
+    fn find_view(&self, template: &OrientedGrid<T>, wildcard: Option<&T>) -> Vec<Coord2U> {
+        let (width, height) = (template.width(), template.height());
+        if width > self.width() || height > self.height() {
+            return Vec::new();
+        }
+        let mut found = Vec::new();
+        for y in 0..=self.height() - height {
+            for x in 0..=self.width() - width {
+                let pos = Coord2U::new2d(x, y);
+                if self.matches_at(template, pos, wildcard) {
+                    found.push(pos);
+                }
+            }
+        }
+        found
+    }
+
+    fn matches_at(&self, template: &OrientedGrid<T>, pos: Coord2U, wildcard: Option<&T>) -> bool {
+        (0..template.height()).all(|y| {
+            (0..template.width()).all(|x| {
+                let offset = Coord2U::new2d(x, y);
+                let expected = &template[offset];
+                Some(expected) == wildcard || &self[pos + offset] == expected
+            })
+        })
+    }
+}
+
+impl<T: PartialEq + Clone> Grid<T, 2> {
+    /// Finds every match of `template`, then sets each cell covered by a non-wildcard template
+    /// cell to `replacement`. Returns the number of matches.
+    pub fn replace_pattern(
+        &mut self,
+        template: &Grid<T, 2>,
+        wildcard: Option<&T>,
+        oriented: bool,
+        replacement: T,
+    ) -> usize {
+        let matches = match oriented {
+            true => self.find_pattern_oriented(template, wildcard),
+            false => self
+                .find_pattern(template, wildcard)
+                .into_iter()
+                .map(|pos| PatternMatch {
+                    pos,
+                    orientation: D4::IDENTITY,
+                })
+                .collect(),
+        };
+        for index in self.matched_cells(template, wildcard, &matches) {
+            self.data[index] = replacement.clone();
+        }
+        matches.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::structs::{coord::Coord2U, grid::Grid};
+
+    fn word_search() -> Grid<char, 2> {
+        Grid::try_from(
+            "MMMSXXMASM\nMSAMXMSMSA\nAMXSXMAAMM\nMSAMASMSMX\nXMASAMXAMM\n\
+             XXAMMXXAMA\nSMSMSASXSS\nSAXAMASAAA\nMAMMMXMMMM\nMXMXAXMASX",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn xmas() {
+        let grid = word_search();
+        let straight = Grid::new2d(vec!['X', 'M', 'A', 'S'], 4).unwrap();
+        let diagonal = Grid::try_from("X...\n.M..\n..A.\n...S").unwrap();
+        assert_eq!(3, grid.find_pattern(&straight, None).len());
+        let count = grid.count_pattern(&straight, None, true)
+            + grid.count_pattern(&diagonal, Some(&'.'), true);
+        assert_eq!(18, count);
+
+        let cross = Grid::try_from("M.S\n.A.\nM.S").unwrap();
+        assert_eq!(9, grid.count_pattern(&cross, Some(&'.'), true));
+    }
+
+    #[test]
+    fn replace() {
+        let mut grid = Grid::try_from("#.##\n####\n.##.").unwrap();
+        let block = Grid::try_from("##\n##").unwrap();
+        assert_eq!(
+            vec![Coord2U::new2d(2, 0), Coord2U::new2d(1, 1)],
+            grid.find_pattern(&block, None)
+        );
+        // overlapping matches replace the union of their cells
+        assert_eq!(2, grid.replace_pattern(&block, None, false, 'O'));
+        assert_eq!("#.OO\n#OOO\n.OO.", grid.to_string(|&c| c));
+
+        // wildcard cells are left as they were
+        let mut grid = Grid::try_from("....\n.#..\n.##.\n....").unwrap();
+        let corner = Grid::try_from("#.\n##").unwrap();
+        assert_eq!(1, grid.replace_pattern(&corner, Some(&'.'), true, 'O'));
+        assert_eq!(3, grid.iter().filter(|&&c| c == 'O').count());
+    }
+}
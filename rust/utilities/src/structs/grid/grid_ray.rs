@@ -0,0 +1,183 @@
+use crate::{
+    enums::{cardinals::Cardinal, intercardinals::Intercardinal},
+    structs::coord::{Coord, Coord2, Coord2U},
+};
+
+use super::{AxisWrap, Grid, GridIndex};
+
+/// Anything that can be turned into a single step through an N-dimensional grid.
+pub trait RayDirection<const N: usize>: Copy {
+    fn step(&self) -> Coord<i64, N>;
+}
+
+impl RayDirection<2> for Cardinal {
+    fn step(&self) -> Coord2 {
+        match self {
+            Cardinal::North => Coord2::new2d(0, -1),
+            Cardinal::East => Coord2::new2d(1, 0),
+            Cardinal::South => Coord2::new2d(0, 1),
+            Cardinal::West => Coord2::new2d(-1, 0),
+        }
+    }
+}
+
+impl RayDirection<2> for Intercardinal {
+    fn step(&self) -> Coord2 {
+        match self {
+            Intercardinal::North => Coord2::new2d(0, -1),
+            Intercardinal::Northeast => Coord2::new2d(1, -1),
+            Intercardinal::East => Coord2::new2d(1, 0),
+            Intercardinal::Southeast => Coord2::new2d(1, 1),
+            Intercardinal::South => Coord2::new2d(0, 1),
+            Intercardinal::Southwest => Coord2::new2d(-1, 1),
+            Intercardinal::West => Coord2::new2d(-1, 0),
+            Intercardinal::Northwest => Coord2::new2d(-1, -1),
+        }
+    }
+}
+
+impl<const N: usize> RayDirection<N> for Coord<i64, N> {
+    fn step(&self) -> Coord<i64, N> {
+        *self
+    }
+}
+
+/// A direction and the first blocking cell seen along it, if any.
+pub type Sighting<'a, T> = (Intercardinal, Option<(Coord2U, &'a T)>);
+
+impl<T, const N: usize> Grid<T, N> {
+    /// Cells visited walking from `from` (exclusive) in `direction` until leaving the grid.
+    /// Returns `None` if `from` is not in the grid.
+    pub fn ray<I, D>(
+        &self,
+        from: I,
+        direction: D,
+    ) -> Option<impl Iterator<Item = (Coord<usize, N>, &T)>>
+    where
+        I: GridIndex<N>,
+        D: RayDirection<N>,
+    {
+        let from = Coord::<i64, N>::from(from.as_coord(self)?);
+        let ray = self
+            .wrapped([AxisWrap::Bounded; N])
+            .ray(from, direction.step())
+            .map(|(pos, value)| {
+                let pos = Coord::<usize, N>::try_from(pos)
+                    .expect("bounded rays never leave the grid");
+                (pos, value)
+            });
+        Some(ray)
+    }
+
+    /// The first cell along the ray that satisfies `predicate`.
+    pub fn first_matching<I, D, P>(
+        &self,
+        from: I,
+        direction: D,
+        predicate: P,
+    ) -> Option<(Coord<usize, N>, &T)>
+    where
+        I: GridIndex<N>,
+        D: RayDirection<N>,
+        P: Fn(&T) -> bool,
+    {
+        self.ray(from, direction)?
+            .find(|(_, value)| predicate(value))
+    }
+}
+
+impl<T> Grid<T, 2> {
+    /// For every direction, the first cell seen from `from` that `blocks` the view, or `None` if
+    /// the view reaches the edge of the grid.
+    pub fn visible_from<I, P>(
+        &self,
+        from: I,
+        include_intercardinals: bool,
+        blocks: P,
+    ) -> Option<Vec<Sighting<'_, T>>>
+    where
+        I: GridIndex<2>,
+        P: Fn(&T) -> bool,
+    {
+        let from = from.as_coord(self)?;
+        let dirs = match include_intercardinals {
+            true => Intercardinal::entries().to_vec(),
+            false => Intercardinal::cardinal_entries().to_vec(),
+        };
+        let visible = dirs
+            .into_iter()
+            .map(|dir| (dir, self.first_matching(from, dir, &blocks)))
+            .collect();
+        Some(visible)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        enums::{cardinals::Cardinal, intercardinals::Intercardinal},
+        structs::{
+            coord::{Coord, Coord2, Coord2U},
+            grid::Grid,
+        },
+    };
+
+    fn trees() -> Grid<u32, 2> {
+        Grid::new2d_map_str("30373\n25512\n65332\n33549\n35390", |c| {
+            c.to_digit(10).unwrap()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn ray() {
+        let trees = trees();
+        let east: Vec<u32> = trees
+            .ray(Coord2U::new2d(1, 2), Cardinal::East)
+            .unwrap()
+            .map(|(_, &height)| height)
+            .collect();
+        assert_eq!(vec![3, 3, 2], east);
+
+        let diagonal: Vec<Coord2U> = trees
+            .ray(0usize, Coord2::new2d(2, 2))
+            .unwrap()
+            .map(|(pos, _)| pos)
+            .collect();
+        assert_eq!(vec![Coord2U::new2d(2, 2), Coord2U::new2d(4, 4)], diagonal);
+
+        assert!(trees.ray(Coord2U::new2d(5, 0), Cardinal::West).is_none());
+
+        let cube = Grid::new3d((0..27).collect(), 3, 3).unwrap();
+        let up: Vec<usize> = cube
+            .ray(Coord::new([1, 1, 0]), Coord::new([0, 0, 1]))
+            .unwrap()
+            .map(|(_, &value)| value)
+            .collect();
+        assert_eq!(vec![13, 22], up);
+    }
+
+    #[test]
+    fn first_matching_and_visible() {
+        let trees = trees();
+        let pos = Coord2U::new2d(2, 3);
+        let height = trees[pos];
+        let blocking = trees.first_matching(pos, Intercardinal::North, |&h| h >= height);
+        assert_eq!(Some((Coord2U::new2d(2, 1), &5)), blocking);
+
+        // the scenic score of the y22d08 example's best tree
+        let score: usize = trees
+            .visible_from(pos, false, |&h| h >= height)
+            .unwrap()
+            .into_iter()
+            .map(|(dir, blocker)| match (dir, blocker) {
+                (_, Some((seen, _))) => seen.manhattan_distance(pos),
+                (Intercardinal::North, None) => pos.y(),
+                (Intercardinal::South, None) => trees.height() - 1 - pos.y(),
+                (Intercardinal::West, None) => pos.x(),
+                (_, None) => trees.width() - 1 - pos.x(),
+            })
+            .product();
+        assert_eq!(8, score);
+    }
+}
@@ -0,0 +1,279 @@
+use crate::structs::coord::{Coord, Coord2};
+
+use super::{Grid, GridIndex};
+
+/// A connected set of cells, where cells connect through shared faces.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Region<const N: usize> {
+    pub label: usize,
+    /// Grid indices of every cell, in the order they were reached.
+    pub cells: Vec<usize>,
+    /// Faces between a cell of this region and anything else, including the grid edge. In 3-D
+    /// this is the surface area.
+    pub perimeter: usize,
+    /// Inclusive bounding box.
+    pub bounds: (Coord<usize, N>, Coord<usize, N>),
+    pub touches_edge: bool,
+}
+
+impl<const N: usize> Region<N> {
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+}
+
+/// Every region of a grid, with each cell labelled by the index of its region.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Regions<const N: usize> {
+    pub labels: Grid<usize, N>,
+    pub regions: Vec<Region<N>>,
+}
+
+impl<const N: usize> Regions<N> {
+    pub fn region_of(&self, index: impl GridIndex<N>) -> Option<&Region<N>> {
+        self.labels.get(index).map(|&label| &self.regions[label])
+    }
+
+    /// Labels of the regions sharing a face with `label`, in ascending order.
+    pub fn adjacent_labels(&self, label: usize) -> Vec<usize> {
+        let mut adjacent: Vec<usize> = self.regions[label]
+            .cells
+            .iter()
+            .flat_map(|&index| self.labels.face_neighbors(index))
+            .flatten()
+            .map(|neighbor| self.labels[neighbor])
+            .filter(|&other| other != label)
+            .collect();
+        adjacent.sort_unstable();
+        adjacent.dedup();
+        adjacent
+    }
+
+    /// Regions enclosed by `label`: those that cannot reach the grid edge without crossing it.
+    /// A pocket made of several touching regions reports every one of them.
+    pub fn holes(&self, label: usize) -> Vec<usize> {
+        // flood everything but `label` in from the grid edge, one region at a time
+        let mut outside = vec![false; self.regions.len()];
+        let mut frontier: Vec<usize> = self
+            .regions
+            .iter()
+            .filter(|region| region.label != label && region.touches_edge)
+            .map(|region| region.label)
+            .collect();
+        for &reached in frontier.iter() {
+            outside[reached] = true;
+        }
+        while let Some(current) = frontier.pop() {
+            for adjacent in self.adjacent_labels(current) {
+                if adjacent != label && !outside[adjacent] {
+                    outside[adjacent] = true;
+                    frontier.push(adjacent);
+                }
+            }
+        }
+        self.regions
+            .iter()
+            .filter(|region| region.label != label && !outside[region.label])
+            .map(|region| region.label)
+            .collect()
+    }
+}
+
+impl Regions<2> {
+    /// Number of straight sides of the region's outline, holes included. Equal to its number
+    /// of corners.
+    pub fn sides(&self, label: usize) -> usize {
+        let inside = |pos: Coord2| self.labels.get(pos) == Some(&label);
+        let corners = [((0, -1), (1, 0)), ((1, 0), (0, 1)), ((0, 1), (-1, 0)), ((-1, 0), (0, -1))];
+        self.regions[label]
+            .cells
+            .iter()
+            .map(|&index| {
+                let pos = Coord2::from(self.labels.coord_of(index).expect("cells are in the grid"));
+                corners
+                    .iter()
+                    .filter(|&&((ax, ay), (bx, by))| {
+                        let a = inside(pos + Coord2::new2d(ax, ay));
+                        let b = inside(pos + Coord2::new2d(bx, by));
+                        let diagonal = inside(pos + Coord2::new2d(ax + bx, ay + by));
+                        (!a && !b) || (a && b && !diagonal)
+                    })
+                    .count()
+            })
+            .sum()
+    }
+}
+
+impl<T, const N: usize> Grid<T, N> {
+    /// Indices of every cell reachable from `start` through cells that satisfy `include`. Empty
+    /// if `start` itself is excluded, `None` if it is outside the grid.
+    pub fn flood_fill<I, F>(&self, start: I, include: F) -> Option<Vec<usize>>
+    where
+        I: GridIndex<N>,
+        F: Fn(&T) -> bool,
+    {
+        let start = start.as_usize(self)?;
+        if !include(&self.data[start]) {
+            return Some(Vec::new());
+        }
+        let mut seen = vec![false; self.len()];
+        seen[start] = true;
+        let mut filled = vec![start];
+        let mut next = 0;
+        while let Some(&index) = filled.get(next) {
+            next += 1;
+            for neighbor in self.face_neighbors(index).flatten() {
+                if !seen[neighbor] && include(&self.data[neighbor]) {
+                    seen[neighbor] = true;
+                    filled.push(neighbor);
+                }
+            }
+        }
+        Some(filled)
+    }
+
+    /// Splits the grid into regions of face-connected cells, where neighbors belong together if
+    /// `same_region` holds for their values. The predicate need not be transitive: a region is
+    /// everything reachable through linked neighbors, and perimeters are counted once every cell
+    /// is labelled, so a face is only perimeter if it separates two different regions.
+    pub fn regions<F>(&self, same_region: F) -> Regions<N>
+    where
+        F: Fn(&T, &T) -> bool,
+    {
+        const UNLABELLED: usize = usize::MAX;
+        let mut labels = vec![UNLABELLED; self.len()];
+        let mut regions = Vec::new();
+        for seed in 0..self.len() {
+            if labels[seed] != UNLABELLED {
+                continue;
+            }
+            let label = regions.len();
+            labels[seed] = label;
+            let seed_pos = self.coord_of(seed).expect("seed is in the grid");
+            let mut region = Region {
+                label,
+                cells: vec![seed],
+                perimeter: 0,
+                bounds: (seed_pos, seed_pos),
+                touches_edge: false,
+            };
+            let mut next = 0;
+            while let Some(&index) = region.cells.get(next) {
+                next += 1;
+                let pos = self.coord_of(index).expect("cells are in the grid");
+                for axis in 0..N {
+                    region.bounds.0.0[axis] = region.bounds.0.0[axis].min(pos.0[axis]);
+                    region.bounds.1.0[axis] = region.bounds.1.0[axis].max(pos.0[axis]);
+                }
+                for neighbor in self.face_neighbors(index) {
+                    match neighbor {
+                        None => region.touches_edge = true,
+                        Some(neighbor)
+                            if labels[neighbor] == UNLABELLED
+                                && same_region(&self.data[index], &self.data[neighbor]) =>
+                        {
+                            labels[neighbor] = label;
+                            region.cells.push(neighbor);
+                        }
+                        Some(_) => {}
+                    }
+                }
+            }
+            regions.push(region);
+        }
+        for (index, &label) in labels.iter().enumerate() {
+            regions[label].perimeter += self
+                .face_neighbors(index)
+                .filter(|neighbor| neighbor.is_none_or(|neighbor| labels[neighbor] != label))
+                .count();
+        }
+        Regions {
+            labels: Grid {
+                data: labels,
+                dimensions: self.dimensions,
+            },
+            regions,
+        }
+    }
+
+    /// The cell across each face of `index`, two per axis, or `None` past the grid edge.
+    fn face_neighbors(&self, index: usize) -> impl Iterator<Item = Option<usize>> + '_ {
+        let pos = Coord::<i64, N>::from(self.coord_of(index).expect("index is in the grid"));
+        pos.face_neighbors()
+            .map(move |neighbor| neighbor.as_usize(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::structs::{
+        coord::{Coord, Coord2U},
+        grid::Grid,
+    };
+
+    fn garden(plots: &str) -> (usize, usize) {
+        let grid = Grid::try_from(plots).unwrap();
+        let regions = grid.regions(|a, b| a == b);
+        regions.regions.iter().fold((0, 0), |(perimeter, sides), region| {
+            (
+                perimeter + region.area() * region.perimeter,
+                sides + region.area() * regions.sides(region.label),
+            )
+        })
+    }
+
+    #[test]
+    fn garden_plots() {
+        assert_eq!((140, 80), garden("AAAA\nBBCD\nBBCC\nEEEC"));
+        assert_eq!((772, 436), garden("OOOOO\nOXOXO\nOOOOO\nOXOXO\nOOOOO"));
+        assert_eq!(236, garden("EEEEE\nEXXXX\nEEEEE\nEXXXX\nEEEEE").1);
+        assert_eq!(368, garden("AAAAAA\nAAABBA\nAAABBA\nABBAAA\nABBAAA\nAAAAAA").1);
+    }
+
+    #[test]
+    fn labels_bounds_and_holes() {
+        let grid = Grid::try_from("OOOOO\nOXOXO\nOOOOO\nOXOXO\nOOOOO").unwrap();
+        let regions = grid.regions(|a, b| a == b);
+        assert_eq!(5, regions.regions.len());
+        let outer = regions.region_of(0usize).unwrap();
+        assert_eq!(21, outer.area());
+        assert!(outer.touches_edge);
+        assert_eq!((Coord2U::new2d(0, 0), Coord2U::new2d(4, 4)), outer.bounds);
+        assert_eq!(vec![1, 2, 3, 4], regions.holes(outer.label));
+        assert_eq!(vec![0], regions.adjacent_labels(1));
+
+        let filled = grid.flood_fill(Coord2U::new2d(1, 1), |&c| c == 'X').unwrap();
+        assert_eq!(vec![6], filled);
+        assert_eq!(21, grid.flood_fill(0usize, |&c| c == 'O').unwrap().len());
+        assert!(grid.flood_fill(Coord2U::new2d(9, 9), |_| true).is_none());
+
+        // a pocket holding two different regions, and a region outside it that touches both
+        let grid = Grid::try_from("OOOOZ\nOXYOZ\nOOOOZ").unwrap();
+        let regions = grid.regions(|a, b| a == b);
+        let label_at = |x, y| regions.region_of(Coord2U::new2d(x, y)).unwrap().label;
+        assert_eq!(vec![label_at(1, 1), label_at(2, 1)], regions.holes(label_at(0, 0)));
+        assert!(regions.holes(label_at(4, 0)).is_empty());
+    }
+
+    #[test]
+    fn surface_area_3d() {
+        // a solid 3x3x3 cube with an air pocket in the middle
+        let cube = Grid::new_with_fn([3, 3, 3], |index| index != 13);
+        let regions = cube.regions(|a, b| a == b);
+        assert_eq!(2, regions.regions.len());
+        let solid = regions.region_of(Coord::new([0, 0, 0])).unwrap();
+        assert_eq!(26, solid.area());
+        assert_eq!(60, solid.perimeter);
+        assert_eq!(vec![1], regions.holes(solid.label));
+        assert_eq!(6, regions.regions[1].perimeter);
+    }
+
+    #[test]
+    fn non_transitive_predicate() {
+        // 0 and 2 are too far apart to link directly, but both link through 1
+        let grid = Grid::new2d(vec![0u8, 2, 1, 2], 2).unwrap();
+        let regions = grid.regions(|a, b| a.abs_diff(*b) <= 1);
+        assert_eq!(1, regions.regions.len());
+        assert_eq!(8, regions.regions[0].perimeter);
+    }
+}
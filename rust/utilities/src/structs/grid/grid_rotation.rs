@@ -1,5 +1,6 @@
 use super::Grid;
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum GridRotation {
     Left,
     Right,
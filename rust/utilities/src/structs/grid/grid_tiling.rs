@@ -0,0 +1,335 @@
+use crate::{
+    enums::cardinals::Cardinal,
+    structs::coord::{Coord, Coord2U},
+};
+
+use super::{D4, Grid, GridError, OrientedGrid};
+
+impl<T: Clone> Grid<T, 2> {
+    /// Splits the grid into `width` x `height` blocks, in row-major order of the blocks, each
+    /// paired with its block position. The grid must divide evenly.
+    pub fn chunks_2d(
+        &self,
+        width: usize,
+        height: usize,
+    ) -> Result<impl Iterator<Item = (Coord2U, Grid<T, 2>)> + '_, GridError> {
+        if width == 0 || height == 0 {
+            return Err(GridError::Empty);
+        }
+        if !self.width().is_multiple_of(width) || !self.height().is_multiple_of(height) {
+            return Err(GridError::UnevenDimensions);
+        }
+        let across = self.width() / width;
+        let blocks = self.len() / (width * height);
+        Ok((0..blocks).map(move |block| {
+            let pos = Coord2U::new2d(block % across, block / across);
+            let start = Coord::new2d(pos.x() * width, pos.y() * height);
+            let sub = self
+                .sub_grid(start, Coord::new2d(width, height))
+                .expect("blocks start inside the grid");
+            (pos, sub)
+        }))
+    }
+
+    /// `chunks_2d`, collected into a grid of blocks.
+    pub fn blocks(&self, width: usize, height: usize) -> Result<Grid<Grid<T, 2>, 2>, GridError> {
+        let across = self.width() / width.max(1);
+        let blocks = self
+            .chunks_2d(width, height)?
+            .map(|(_, block)| block)
+            .collect();
+        Grid::new2d(blocks, across)
+    }
+
+    /// Stitches a grid of blocks back into one grid. Blocks in the same row must share a height
+    /// and blocks in the same column must share a width.
+    pub fn from_blocks(blocks: &Grid<Grid<T, 2>, 2>) -> Result<Self, GridError> {
+        let widths: Vec<usize> = (0..blocks.width())
+            .map(|x| blocks[Coord2U::new2d(x, 0)].width())
+            .collect();
+        let heights: Vec<usize> = (0..blocks.height())
+            .map(|y| blocks[Coord2U::new2d(0, y)].height())
+            .collect();
+        if blocks.iter_with_coords().any(|(pos, block)| {
+            block.width() != widths[pos.x()] || block.height() != heights[pos.y()]
+        }) {
+            return Err(GridError::UnevenDimensions);
+        }
+        let width = widths.iter().sum();
+        let mut data = Vec::with_capacity(width * heights.iter().sum::<usize>());
+        for (by, &height) in heights.iter().enumerate() {
+            for y in 0..height {
+                for bx in 0..widths.len() {
+                    let block = &blocks[Coord2U::new2d(bx, by)];
+                    data.extend(block.row(y).expect("row is within the block").cloned());
+                }
+            }
+        }
+        Grid::new2d(data, width)
+    }
+}
+
+/// The four edges of a tile as bit patterns. North and south edges read west to east, east and
+/// west edges read north to south, so touching edges of neighboring tiles compare equal.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct EdgeBits {
+    pub north: u64,
+    pub east: u64,
+    pub south: u64,
+    pub west: u64,
+}
+
+impl EdgeBits {
+    pub fn side(&self, side: Cardinal) -> u64 {
+        match side {
+            Cardinal::North => self.north,
+            Cardinal::East => self.east,
+            Cardinal::South => self.south,
+            Cardinal::West => self.west,
+        }
+    }
+}
+
+/// Reverses the lowest `length` bits, for comparing an edge read from the other end.
+pub fn reverse_bits(bits: u64, length: usize) -> u64 {
+    if length == 0 {
+        0
+    } else {
+        bits.reverse_bits() >> (64 - length)
+    }
+}
+
+impl<'a, T> OrientedGrid<'a, T> {
+    /// Cells along one edge, read west to east or north to south.
+    pub fn edge(&self, side: Cardinal) -> Vec<&'a T> {
+        let (width, height) = (self.width(), self.height());
+        let pos = |i: usize| match side {
+            Cardinal::North => Coord2U::new2d(i, 0),
+            Cardinal::South => Coord2U::new2d(i, height - 1),
+            Cardinal::West => Coord2U::new2d(0, i),
+            Cardinal::East => Coord2U::new2d(width - 1, i),
+        };
+        let length = match side {
+            Cardinal::North | Cardinal::South => width,
+            Cardinal::East | Cardinal::West => height,
+        };
+        (0..length)
+            .map(|i| self.get(pos(i)).expect("edges are inside the view"))
+            .collect()
+    }
+
+    /// Edges as bit patterns, with the first cell of each edge in the most significant bit.
+    /// Panics if either side is longer than the 64 bits a pattern can hold.
+    pub fn edge_bits<F>(&self, is_set: F) -> EdgeBits
+    where
+        F: Fn(&T) -> bool,
+    {
+        assert!(
+            self.width() <= 64 && self.height() <= 64,
+            "Edge bit patterns are limited to 64 cells per side."
+        );
+        let bits = |side| {
+            self.edge(side)
+                .into_iter()
+                .fold(0, |acc, cell| (acc << 1) | is_set(cell) as u64)
+        };
+        EdgeBits {
+            north: bits(Cardinal::North),
+            east: bits(Cardinal::East),
+            south: bits(Cardinal::South),
+            west: bits(Cardinal::West),
+        }
+    }
+}
+
+impl<T> Grid<T, 2> {
+    pub fn edge_bits<F>(&self, is_set: F) -> EdgeBits
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.oriented(D4::IDENTITY).edge_bits(is_set)
+    }
+
+    /// Edge bit patterns for each of the eight orientations.
+    pub fn edge_bits_all<F>(&self, is_set: F) -> [(D4, EdgeBits); 8]
+    where
+        F: Fn(&T) -> bool,
+    {
+        D4::entries()
+            .map(|orientation| (orientation, self.oriented(orientation).edge_bits(&is_set)))
+    }
+}
+
+/// Whether the edge of `a` facing `side` is identical to the facing edge of `b`.
+pub fn edges_match<T: PartialEq>(a: &OrientedGrid<T>, b: &OrientedGrid<T>, side: Cardinal) -> bool {
+    a.edge(side) == b.edge(side.flip())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Placement {
+    pub tile: usize,
+    pub orientation: D4,
+}
+
+/// Arranges every tile into a `width` x `height` layout, trying each orientation, so that
+/// `fits(a, b, side)` holds whenever `b` is placed on `side` of `a`. Tiles are placed in
+/// row-major order with backtracking, so only west and north neighbors are ever checked.
+pub fn solve_jigsaw<T, F>(
+    tiles: &[Grid<T, 2>],
+    width: usize,
+    height: usize,
+    fits: F,
+) -> Option<Grid<Placement, 2>>
+where
+    F: Fn(&OrientedGrid<T>, &OrientedGrid<T>, Cardinal) -> bool,
+{
+    if width * height != tiles.len() || tiles.is_empty() {
+        return None;
+    }
+    let mut jigsaw = Jigsaw {
+        tiles,
+        width,
+        fits,
+        used: vec![false; tiles.len()],
+        placed: Vec::with_capacity(tiles.len()),
+    };
+    if jigsaw.place() {
+        Grid::new2d(jigsaw.placed, width).ok()
+    } else {
+        None
+    }
+}
+
+struct Jigsaw<'a, T, F> {
+    tiles: &'a [Grid<T, 2>],
+    width: usize,
+    fits: F,
+    used: Vec<bool>,
+    placed: Vec<Placement>,
+}
+
+impl<T, F> Jigsaw<'_, T, F>
+where
+    F: Fn(&OrientedGrid<T>, &OrientedGrid<T>, Cardinal) -> bool,
+{
+    fn view(&self, placement: Placement) -> OrientedGrid<'_, T> {
+        self.tiles[placement.tile].oriented(placement.orientation)
+    }
+
+    fn place(&mut self) -> bool {
+        let slot = self.placed.len();
+        if slot == self.tiles.len() {
+            return true;
+        }
+        let west = (!slot.is_multiple_of(self.width)).then(|| self.placed[slot - 1]);
+        let north = (slot >= self.width).then(|| self.placed[slot - self.width]);
+        for tile in 0..self.tiles.len() {
+            if self.used[tile] {
+                continue;
+            }
+            for orientation in D4::entries() {
+                let candidate = Placement { tile, orientation };
+                let view = self.view(candidate);
+                let fits_west =
+                    west.is_none_or(|west| (self.fits)(&self.view(west), &view, Cardinal::East));
+                let fits_north = north
+                    .is_none_or(|north| (self.fits)(&self.view(north), &view, Cardinal::South));
+                if !fits_west || !fits_north {
+                    continue;
+                }
+                self.used[tile] = true;
+                self.placed.push(candidate);
+                if self.place() {
+                    return true;
+                }
+                self.placed.pop();
+                self.used[tile] = false;
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::grid::GridIterator;
+
+    #[test]
+    fn chunks_and_reassembly() {
+        let grid: Grid<usize, 2> = (0..24).try_collect_grid(6).unwrap();
+        let chunks: Vec<_> = grid.chunks_2d(3, 2).unwrap().collect();
+        assert_eq!(4, chunks.len());
+        assert_eq!(Coord2U::new2d(1, 1), chunks[3].0);
+        assert_eq!(vec![15, 16, 17, 21, 22, 23], chunks[3].1.data);
+        assert!(grid.chunks_2d(4, 2).is_err());
+
+        let blocks = grid.blocks(2, 2).unwrap();
+        assert_eq!([3, 2], blocks.dimensions);
+        assert_eq!(grid, Grid::from_blocks(&blocks).unwrap());
+
+        // blocks may grow, as when y17d21 enhances 3x3 squares into 4x4 ones
+        let enhanced: Vec<Grid<usize, 2>> = blocks
+            .iter()
+            .map(|block| Grid::new2d_with_fn(3, 3, |_| block[0]))
+            .collect();
+        let enhanced = Grid::from_blocks(&Grid::new2d(enhanced, 3).unwrap()).unwrap();
+        assert_eq!([9, 6], enhanced.dimensions);
+        assert_eq!(16, enhanced[Coord2U::new2d(8, 5)]);
+    }
+
+    #[test]
+    fn edges() {
+        let tile = Grid::try_from("#..\n..#\n##.").unwrap();
+        let bits = tile.edge_bits(|&c| c == '#');
+        assert_eq!(
+            EdgeBits {
+                north: 0b100,
+                east: 0b010,
+                south: 0b110,
+                west: 0b101,
+            },
+            bits
+        );
+        assert_eq!(0b011, reverse_bits(bits.south, 3));
+        let all = tile.edge_bits_all(|&c| c == '#');
+        let (_, flipped) = all.iter().find(|(o, _)| *o == D4::new(0, true)).unwrap();
+        assert_eq!(reverse_bits(bits.north, 3), flipped.north);
+        assert_eq!(bits.west, flipped.east);
+    }
+
+    #[test]
+    fn jigsaw() {
+        // a 28x28 image cut into 3x3 overlapping 10x10 tiles, as in y20d20
+        let image = (0..28 * 28)
+            .scan(12345u64, |seed, _| {
+                *seed = seed
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                Some(*seed >> 63 == 1)
+            })
+            .try_collect_grid(28)
+            .unwrap();
+        let shuffle = [4, 7, 0, 8, 2, 5, 1, 6, 3];
+        let tiles: Vec<Grid<bool, 2>> = shuffle
+            .iter()
+            .enumerate()
+            .map(|(i, &original)| {
+                let start = Coord::new2d(original % 3 * 9, original / 3 * 9);
+                let tile = image.sub_grid(start, Coord::new2d(10, 10)).unwrap();
+                tile.orient(D4::entries()[i % 8])
+            })
+            .collect();
+
+        let solved = solve_jigsaw(&tiles, 3, 3, edges_match).unwrap();
+        let mut corners: Vec<usize> = [0, 2, 6, 8]
+            .iter()
+            .map(|&slot| shuffle[solved[slot].tile])
+            .collect();
+        corners.sort();
+        assert_eq!(vec![0, 2, 6, 8], corners);
+        assert_eq!(4, shuffle[solved[4].tile]);
+
+        assert!(solve_jigsaw(&tiles, 2, 4, edges_match).is_none());
+    }
+}
@@ -0,0 +1,270 @@
+use crate::{
+    enums::{cardinals::Cardinal, intercardinals::Intercardinal},
+    structs::coord::{Coord, Coord2},
+};
+
+use super::{Grid, RayDirection};
+
+/// How positions outside the grid are treated along one axis.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AxisWrap {
+    /// Positions past the edge are out of bounds, as with `Grid::get`.
+    Bounded,
+    /// Positions past the edge are pulled back to the nearest edge cell.
+    Clamp,
+    /// Positions past one edge continue from the opposite edge.
+    Torus,
+}
+
+/// A read-only view of a `Grid` that resolves out-of-range positions according to a per-axis
+/// `AxisWrap`, and optionally treats anything still out of bounds as a virtual border cell.
+#[derive(Debug)]
+pub struct WrappedGrid<'a, T, const N: usize> {
+    pub grid: &'a Grid<T, N>,
+    pub axes: [AxisWrap; N],
+    pub border: Option<&'a T>,
+}
+
+// manual impls so the view is copyable without requiring `T: Copy`
+impl<T, const N: usize> Clone for WrappedGrid<'_, T, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, const N: usize> Copy for WrappedGrid<'_, T, N> {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WrappedAdjacent<'a, T> {
+    /// Position inside the grid after wrapping, or the raw position for a border cell.
+    pub pos: Coord2,
+    pub index: Option<usize>,
+    pub dir: Intercardinal,
+    pub value: &'a T,
+}
+
+impl<T, const N: usize> Grid<T, N> {
+    pub fn wrapped(&self, axes: [AxisWrap; N]) -> WrappedGrid<'_, T, N> {
+        WrappedGrid {
+            grid: self,
+            axes,
+            border: None,
+        }
+    }
+
+    pub fn torus(&self) -> WrappedGrid<'_, T, N> {
+        self.wrapped([AxisWrap::Torus; N])
+    }
+
+    /// Treats every position outside the grid as holding `border`.
+    pub fn bordered<'a>(&'a self, border: &'a T) -> WrappedGrid<'a, T, N> {
+        self.wrapped([AxisWrap::Bounded; N]).with_border(border)
+    }
+}
+
+impl<'a, T, const N: usize> WrappedGrid<'a, T, N> {
+    pub fn with_border(self, border: &'a T) -> Self {
+        Self {
+            border: Some(border),
+            ..self
+        }
+    }
+
+    /// Maps a position onto a cell of the underlying grid, or `None` if it falls outside. An
+    /// empty axis has no cell to wrap or clamp to, so nothing resolves on it.
+    pub fn resolve(&self, pos: Coord<i64, N>) -> Option<Coord<usize, N>> {
+        let mut resolved = [0; N];
+        for (axis, wrap) in self.axes.iter().enumerate() {
+            let size = self.grid.dimensions[axis] as i64;
+            if size == 0 {
+                return None;
+            }
+            let value = pos.0[axis];
+            resolved[axis] = match wrap {
+                AxisWrap::Bounded => {
+                    if !(0..size).contains(&value) {
+                        return None;
+                    }
+                    value
+                }
+                AxisWrap::Clamp => value.clamp(0, size - 1),
+                AxisWrap::Torus => value.rem_euclid(size),
+            } as usize;
+        }
+        Some(Coord(resolved))
+    }
+
+    pub fn get(&self, pos: Coord<i64, N>) -> Option<&'a T> {
+        match self.resolve(pos) {
+            Some(resolved) => Some(&self.grid[resolved]),
+            None => self.border,
+        }
+    }
+
+    /// Like `get`, but also returns the position the value was read from: the wrapped position
+    /// for grid cells and the original position for border cells.
+    fn locate(&self, pos: Coord<i64, N>) -> Option<(Coord<i64, N>, Option<usize>, &'a T)> {
+        match self.resolve(pos) {
+            Some(resolved) => {
+                let index = resolved
+                    .get_index(&self.grid.dimensions)
+                    .expect("resolved positions are always in range");
+                Some((resolved.into(), Some(index), &self.grid[index]))
+            }
+            None => self.border.map(|border| (pos, None, border)),
+        }
+    }
+
+    /// Walks from `from` (exclusive) by repeatedly adding `step`. Stops on leaving a bounded
+    /// axis, one cell into the border if there is one, or when clamping stops the walk from
+    /// making progress. On a torus that check ends a ray at once if every step lands back on
+    /// the cell it left, as when the step is a multiple of each axis size; any other torus ray
+    /// never ends on its own.
+    pub fn ray(
+        &self,
+        from: Coord<i64, N>,
+        step: Coord<i64, N>,
+    ) -> impl Iterator<Item = (Coord<i64, N>, &'a T)> + use<'a, T, N> {
+        let view = *self;
+        let mut current = from;
+        let mut last = view.resolve(from).map(Coord::<i64, N>::from);
+        let mut done = step == Coord::origin();
+        std::iter::from_fn(move || {
+            if done {
+                return None;
+            }
+            current += step;
+            let (pos, index, value) = view.locate(current)?;
+            if index.is_none() {
+                done = true;
+            } else if last == Some(pos) {
+                return None;
+            }
+            last = Some(pos);
+            Some((pos, value))
+        })
+    }
+}
+
+impl<'a, T> WrappedGrid<'a, T, 2> {
+    pub fn adjacent(
+        &self,
+        pos: Coord2,
+        include_intercardinals: bool,
+    ) -> impl Iterator<Item = WrappedAdjacent<'a, T>> + 'a {
+        let view = *self;
+        let dirs = match include_intercardinals {
+            true => Intercardinal::entries().to_vec(),
+            false => Intercardinal::cardinal_entries().to_vec(),
+        };
+        dirs.into_iter().filter_map(move |dir| {
+            let (pos, index, value) = view.locate(pos + dir.step())?;
+            Some(WrappedAdjacent {
+                pos,
+                index,
+                dir,
+                value,
+            })
+        })
+    }
+
+    /// Moves `distance` cells in `dir`, wrapping as needed. Returns `None` if the destination is
+    /// out of bounds and there is no border.
+    pub fn move_direction(&self, pos: Coord2, dir: Cardinal, distance: i64) -> Option<Coord2> {
+        let moved = pos.move_direction(dir, distance)?;
+        self.locate(moved).map(|(pos, _, _)| pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        enums::cardinals::Cardinal,
+        structs::{
+            coord::Coord2,
+            grid::{AxisWrap, Grid, GridIterator},
+        },
+    };
+
+    fn num_pad() -> Grid<usize, 2> {
+        (1..=9).try_collect_grid(3).unwrap()
+    }
+
+    #[test]
+    fn get() {
+        let np = num_pad();
+        assert_eq!(Some(&9), np.torus().get(Coord2::new2d(-1, -1)));
+        assert_eq!(Some(&4), np.torus().get(Coord2::new2d(6, 7)));
+        assert_eq!(Some(&1), np.wrapped([AxisWrap::Clamp; 2]).get(Coord2::new2d(-5, -5)));
+        assert_eq!(
+            None,
+            np.wrapped([AxisWrap::Torus, AxisWrap::Bounded])
+                .get(Coord2::new2d(5, 3))
+        );
+        assert_eq!(Some(&0), np.bordered(&0).get(Coord2::new2d(3, 1)));
+        assert_eq!(Some(&6), np.bordered(&0).get(Coord2::new2d(2, 1)));
+
+        let empty = Grid::new2d_with_fn(0, 3, |i| i);
+        assert_eq!(None, empty.torus().get(Coord2::new2d(0, 1)));
+        assert_eq!(None, empty.wrapped([AxisWrap::Clamp; 2]).get(Coord2::new2d(0, 1)));
+        assert_eq!(Some(&0), empty.bordered(&0).get(Coord2::new2d(0, 1)));
+    }
+
+    #[test]
+    fn adjacent() {
+        let np = num_pad();
+        let corner: Vec<usize> = np
+            .torus()
+            .adjacent(Coord2::origin(), true)
+            .map(|adjacent| *adjacent.value)
+            .collect();
+        assert_eq!(vec![9, 7, 8, 2, 5, 4, 6, 3], corner);
+
+        let bordered: Vec<_> = np
+            .bordered(&0)
+            .adjacent(Coord2::origin(), false)
+            .map(|adjacent| (adjacent.index, *adjacent.value))
+            .collect();
+        assert_eq!(vec![(None, 0), (Some(1), 2), (Some(3), 4), (None, 0)], bordered);
+    }
+
+    #[test]
+    fn ray_and_move() {
+        let np = num_pad();
+        let east: Vec<usize> = np
+            .torus()
+            .ray(Coord2::new2d(0, 1), Coord2::new2d(1, 0))
+            .take(5)
+            .map(|(_, &value)| value)
+            .collect();
+        assert_eq!(vec![5, 6, 4, 5, 6], east);
+
+        let clamped: Vec<usize> = np
+            .wrapped([AxisWrap::Clamp; 2])
+            .ray(Coord2::origin(), Coord2::new2d(1, 1))
+            .map(|(_, &value)| value)
+            .collect();
+        assert_eq!(vec![5, 9], clamped);
+
+        let bordered: Vec<usize> = np
+            .bordered(&0)
+            .ray(Coord2::origin(), Coord2::new2d(0, 1))
+            .map(|(_, &value)| value)
+            .collect();
+        assert_eq!(vec![4, 7, 0], bordered);
+
+        let lapping = np.torus().ray(Coord2::new2d(0, 1), Coord2::new2d(3, 0));
+        assert_eq!(0, lapping.count());
+
+        let torus = np.torus();
+        assert_eq!(
+            Some(Coord2::new2d(2, 1)),
+            torus.move_direction(Coord2::new2d(0, 1), Cardinal::West, 4)
+        );
+        assert_eq!(
+            None,
+            np.wrapped([AxisWrap::Bounded; 2])
+                .move_direction(Coord2::origin(), Cardinal::North, 1)
+        );
+    }
+}
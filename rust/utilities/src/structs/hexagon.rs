@@ -1,6 +1,135 @@
 use std::fmt::Display;
+use std::ops::{Add, Mul, Sub};
 
+use crate::automaton::Neighborhood;
 use crate::enums::intercardinals::Intercardinal;
+use crate::structs::coord::Coord2;
+use crate::structs::sparse_grid::SparseGrid;
+
+/// A step between neighboring hexagons, in axial coordinates where `q` runs east and `r` runs
+/// south-southwest.
+pub trait HexDirection: Copy {
+    fn offset(&self) -> Hexagon;
+}
+
+/// Directions between flat-topped hexagons, whose columns line up vertically, in clockwise
+/// order. These match the moves of `Hexagon::hex_at`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FlatDirection {
+    North,
+    Northeast,
+    Southeast,
+    South,
+    Southwest,
+    Northwest,
+}
+
+impl FlatDirection {
+    pub fn new(direction: &str) -> Option<Self> {
+        match direction.to_ascii_uppercase().as_str() {
+            "N" => Some(Self::North),
+            "NE" => Some(Self::Northeast),
+            "SE" => Some(Self::Southeast),
+            "S" => Some(Self::South),
+            "SW" => Some(Self::Southwest),
+            "NW" => Some(Self::Northwest),
+            _ => None,
+        }
+    }
+
+    pub fn entries() -> [Self; 6] {
+        [
+            Self::North,
+            Self::Northeast,
+            Self::Southeast,
+            Self::South,
+            Self::Southwest,
+            Self::Northwest,
+        ]
+    }
+
+    /// Parses comma-separated moves like `ne,se,n`, as in y17d11.
+    pub fn parse_moves(moves: &str) -> Option<Vec<Self>> {
+        moves.trim().split(',').map(|dir| Self::new(dir.trim())).collect()
+    }
+}
+
+impl HexDirection for FlatDirection {
+    fn offset(&self) -> Hexagon {
+        match self {
+            Self::North => Hexagon::new(0, -1),
+            Self::Northeast => Hexagon::new(1, -1),
+            Self::Southeast => Hexagon::new(1, 0),
+            Self::South => Hexagon::new(0, 1),
+            Self::Southwest => Hexagon::new(-1, 1),
+            Self::Northwest => Hexagon::new(-1, 0),
+        }
+    }
+}
+
+/// Directions between pointy-topped hexagons, whose rows line up horizontally, in clockwise
+/// order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PointyDirection {
+    East,
+    Southeast,
+    Southwest,
+    West,
+    Northwest,
+    Northeast,
+}
+
+impl PointyDirection {
+    pub fn new(direction: &str) -> Option<Self> {
+        match direction.to_ascii_uppercase().as_str() {
+            "E" => Some(Self::East),
+            "SE" => Some(Self::Southeast),
+            "SW" => Some(Self::Southwest),
+            "W" => Some(Self::West),
+            "NW" => Some(Self::Northwest),
+            "NE" => Some(Self::Northeast),
+            _ => None,
+        }
+    }
+
+    pub fn entries() -> [Self; 6] {
+        [
+            Self::East,
+            Self::Southeast,
+            Self::Southwest,
+            Self::West,
+            Self::Northwest,
+            Self::Northeast,
+        ]
+    }
+
+    /// Parses undelimited moves like `esenee`, as in y20d24. An `n` or `s` always pairs with
+    /// the letter after it.
+    pub fn parse_moves(moves: &str) -> Option<Vec<Self>> {
+        let mut parsed = Vec::new();
+        let mut rest = moves.trim();
+        while !rest.is_empty() {
+            let length = if rest.starts_with(['n', 's', 'N', 'S']) { 2 } else { 1 };
+            let token = rest.get(..length)?;
+            parsed.push(Self::new(token)?);
+            rest = &rest[length..];
+        }
+        Some(parsed)
+    }
+}
+
+impl HexDirection for PointyDirection {
+    fn offset(&self) -> Hexagon {
+        match self {
+            Self::East => Hexagon::new(1, 0),
+            Self::Southeast => Hexagon::new(0, 1),
+            Self::Southwest => Hexagon::new(-1, 1),
+            Self::West => Hexagon::new(-1, 0),
+            Self::Northwest => Hexagon::new(0, -1),
+            Self::Northeast => Hexagon::new(1, -1),
+        }
+    }
+}
 
 #[derive(PartialEq, Eq, Copy, Clone, Debug, Hash)]
 pub struct Hexagon {
@@ -9,6 +138,10 @@ pub struct Hexagon {
 }
 
 impl Hexagon {
+    pub fn new(q: i64, r: i64) -> Self {
+        Self { q, r }
+    }
+
     pub fn origin() -> Self {
         Self { q: 0, r: 0 }
     }
@@ -46,6 +179,171 @@ impl Hexagon {
             self.hex_at(Intercardinal::Southwest),
         ]
     }
+
+    pub fn neighbor<D: HexDirection>(&self, dir: D) -> Self {
+        *self + dir.offset()
+    }
+
+    pub fn moved<D: HexDirection>(&self, dir: D, distance: i64) -> Self {
+        *self + dir.offset() * distance
+    }
+
+    /// Where a sequence of moves ends up.
+    pub fn walk<D: HexDirection>(&self, moves: impl IntoIterator<Item = D>) -> Self {
+        moves.into_iter().fold(*self, |hex, dir| hex.neighbor(dir))
+    }
+
+    /// Distance from the origin.
+    pub fn length(&self) -> usize {
+        self.distance(Self::origin())
+    }
+
+    /// Every hexagon exactly `radius` steps away, clockwise from the one straight north in the
+    /// flat layout. A radius of zero gives just this hexagon.
+    pub fn ring(&self, radius: usize) -> Vec<Self> {
+        if radius == 0 {
+            return vec![*self];
+        }
+        let mut hex = self.moved(FlatDirection::North, radius as i64);
+        let mut ring = Vec::with_capacity(6 * radius);
+        for dir in FlatDirection::entries().into_iter().cycle().skip(2).take(6) {
+            for _ in 0..radius {
+                ring.push(hex);
+                hex = hex.neighbor(dir);
+            }
+        }
+        ring
+    }
+
+    /// Every hexagon within `radius` steps, ring by ring outwards from this one.
+    pub fn spiral(&self, radius: usize) -> Vec<Self> {
+        (0..=radius).flat_map(|r| self.ring(r)).collect()
+    }
+
+    /// The hexagons a straight line to `other` passes through, including both ends, with each
+    /// one neighboring the next.
+    pub fn line_to(&self, other: Self) -> Vec<Self> {
+        let steps = self.distance(other);
+        if steps == 0 {
+            return vec![*self];
+        }
+        // nudge off exact edges so ties always round the same way
+        let start = (self.q as f64 + 1e-6, self.r as f64 + 1e-6);
+        let end = (other.q as f64 + 1e-6, other.r as f64 + 1e-6);
+        (0..=steps)
+            .map(|i| {
+                let t = i as f64 / steps as f64;
+                Self::round(start.0 + (end.0 - start.0) * t, start.1 + (end.1 - start.1) * t)
+            })
+            .collect()
+    }
+
+    /// The nearest hexagon to fractional axial coordinates.
+    pub fn round(q: f64, r: f64) -> Self {
+        let s = -q - r;
+        let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+        let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+        if dq > dr && dq > ds {
+            rq = -rr - rs;
+        } else if dr > ds {
+            rr = -rq - rs;
+        }
+        Self::new(rq as i64, rr as i64)
+    }
+
+    /// Rotates around `center` by `sixths` sixths of a turn clockwise; negative turns go
+    /// counterclockwise.
+    pub fn rotate_around(&self, center: Self, sixths: i64) -> Self {
+        let mut vector = *self - center;
+        for _ in 0..sixths.rem_euclid(6) {
+            vector = Self::new(-vector.r, -vector.s());
+        }
+        center + vector
+    }
+
+    /// The six neighbor offsets as a neighborhood, so an `Automaton` over a `HexGrid` steps
+    /// like a hexagonal Game of Life.
+    pub fn neighborhood<T>() -> Neighborhood<T, 2> {
+        Neighborhood::Offsets(
+            FlatDirection::entries()
+                .iter()
+                .map(|dir| Coord2::from(dir.offset()))
+                .collect(),
+        )
+    }
+}
+
+impl Add for Hexagon {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.q + rhs.q, self.r + rhs.r)
+    }
+}
+
+impl Sub for Hexagon {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.q - rhs.q, self.r - rhs.r)
+    }
+}
+
+impl Mul<i64> for Hexagon {
+    type Output = Self;
+
+    fn mul(self, rhs: i64) -> Self {
+        Self::new(self.q * rhs, self.r * rhs)
+    }
+}
+
+impl From<Hexagon> for Coord2 {
+    fn from(hex: Hexagon) -> Self {
+        Coord2::new2d(hex.q, hex.r)
+    }
+}
+
+impl From<Coord2> for Hexagon {
+    fn from(pos: Coord2) -> Self {
+        Self::new(pos.x(), pos.y())
+    }
+}
+
+/// A sparse grid of hexagons, stored at `(q, r)`.
+pub type HexGrid<T> = SparseGrid<T, 2>;
+
+/// Access to a `HexGrid` by hexagon, kept in a trait so that other 2-D sparse grids only gain
+/// these methods where it is imported.
+pub trait HexCells<T> {
+    /// Returns the cell only if it has been explicitly stored, like `get_stored`.
+    fn get_hex(&self, hex: Hexagon) -> Option<&T>;
+
+    fn insert_hex(&mut self, hex: Hexagon, value: T) -> Option<T>
+    where
+        T: PartialEq;
+
+    /// The six neighbors of `hex` with their values, including background cells.
+    fn hex_neighbors(&self, hex: Hexagon) -> [(Hexagon, &T); 6];
+}
+
+impl<T> HexCells<T> for HexGrid<T> {
+    fn get_hex(&self, hex: Hexagon) -> Option<&T> {
+        self.get_stored(hex.into())
+    }
+
+    fn insert_hex(&mut self, hex: Hexagon, value: T) -> Option<T>
+    where
+        T: PartialEq,
+    {
+        self.insert(hex.into(), value)
+    }
+
+    fn hex_neighbors(&self, hex: Hexagon) -> [(Hexagon, &T); 6] {
+        FlatDirection::entries().map(|dir| {
+            let neighbor = hex.neighbor(dir);
+            (neighbor, self.get(neighbor.into()))
+        })
+    }
 }
 
 impl Display for Hexagon {
@@ -65,3 +363,125 @@ impl PartialOrd for Hexagon {
         Some(self.cmp(other))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automaton::Automaton;
+
+    #[test]
+    fn flat_moves() {
+        // y17d11 examples
+        let distances: Vec<usize> = ["ne,ne,ne", "ne,ne,sw,sw", "ne,ne,s,s", "se,sw,se,sw,sw"]
+            .iter()
+            .map(|moves| {
+                let moves = FlatDirection::parse_moves(moves).unwrap();
+                Hexagon::origin().walk(moves).length()
+            })
+            .collect();
+        assert_eq!(vec![3, 0, 2, 3], distances);
+        assert_eq!(None, FlatDirection::parse_moves("ne,e"));
+        for (dir, intercardinal) in FlatDirection::entries().into_iter().zip([
+            Intercardinal::North,
+            Intercardinal::Northeast,
+            Intercardinal::Southeast,
+            Intercardinal::South,
+            Intercardinal::Southwest,
+            Intercardinal::Northwest,
+        ]) {
+            assert_eq!(Hexagon::origin().hex_at(intercardinal), Hexagon::origin().neighbor(dir));
+        }
+    }
+
+    #[test]
+    fn pointy_moves() {
+        use PointyDirection::*;
+        let moves = PointyDirection::parse_moves("esenee").unwrap();
+        assert_eq!(vec![East, Southeast, Northeast, East], moves);
+        assert_eq!(Hexagon::new(3, 0), Hexagon::origin().walk(moves));
+        let back = PointyDirection::parse_moves("nwwswee").unwrap();
+        assert_eq!(Hexagon::origin(), Hexagon::origin().walk(back));
+        assert_eq!(None, PointyDirection::parse_moves("esn"));
+    }
+
+    #[test]
+    fn rings_and_lines() {
+        let center = Hexagon::new(2, -5);
+        assert_eq!(vec![center], center.ring(0));
+        for radius in 1..5 {
+            let ring = center.ring(radius);
+            assert_eq!(6 * radius, ring.len());
+            assert!(ring.iter().all(|hex| hex.distance(center) == radius));
+            assert!(ring.windows(2).all(|pair| pair[0].distance(pair[1]) == 1));
+        }
+        let spiral = center.spiral(3);
+        assert_eq!(37, spiral.len());
+        assert_eq!(37, spiral.iter().collect::<std::collections::HashSet<_>>().len());
+
+        for other in Hexagon::origin().spiral(4) {
+            let line = center.line_to(other);
+            assert_eq!(center.distance(other) + 1, line.len());
+            assert_eq!((Some(&center), Some(&other)), (line.first(), line.last()));
+            assert!(line.windows(2).all(|pair| pair[0].distance(pair[1]) == 1));
+        }
+    }
+
+    #[test]
+    fn rotation() {
+        let center = Hexagon::new(1, 1);
+        let hex = Hexagon::new(4, -2);
+        assert_eq!(hex, hex.rotate_around(center, 6));
+        assert_eq!(hex, hex.rotate_around(center, 2).rotate_around(center, -2));
+        assert_eq!(
+            center.neighbor(FlatDirection::Southeast),
+            center.neighbor(FlatDirection::Northeast).rotate_around(center, 1)
+        );
+        for turns in 0..6 {
+            assert_eq!(center.distance(hex), center.distance(hex.rotate_around(center, turns)));
+        }
+    }
+
+    #[test]
+    fn lobby_layout() {
+        // y20d24 example
+        let tiles = "sesenwnenenewseeswwswswwnenewsewsw\nneeenesenwnwwswnenewnwwsewnenwseswesw\n\
+                     seswneswswsenwwnwse\nnwnwneseeswswnenewneswwnewseswneseene\n\
+                     swweswneswnenwsewnwneneseenw\neesenwseswswnenwswnwnwsewwnwsene\n\
+                     sewnenenenesenwsewnenwwwse\nwenwwweseeeweswwwnwwe\n\
+                     wsweesenenewnwwnwsenewsenwwsesesenwne\nneeswseenwwswnwswswnw\n\
+                     nenwswwsewswnenenewsenwsenwnesesenew\nenewnwewneswsewnwswenweswnenwsenwsw\n\
+                     sweneswneswneneenwnewenewwneswswnese\nswwesenesewenwneswnwwneseswwne\n\
+                     enesenwswwswneneswsenwnewswseenwsese\nwnwnesenesenenwwnenwsewesewsesesew\n\
+                     nenewswnwewswnenesenwnesewesw\neneswnwswnwsenenwnwnwwseeswneewsenese\n\
+                     neswnwewnwnwseenwseesewsenwsweewe\nwseweeenwnesenwwwswnew";
+        let mut grid = HexGrid::new(false);
+        for line in tiles.lines() {
+            let tile = Hexagon::origin().walk(PointyDirection::parse_moves(line).unwrap());
+            let flipped = !grid.get_hex(tile).is_some_and(|&black| black);
+            grid.insert_hex(tile, flipped);
+        }
+        assert_eq!(10, grid.len());
+        assert_eq!(None, grid.get_hex(Hexagon::new(100, 100)));
+        let neighbors = grid.hex_neighbors(Hexagon::origin());
+        assert_eq!(Hexagon::origin().ring(1).len(), neighbors.len());
+        assert!(
+            neighbors
+                .iter()
+                .all(|&(hex, &black)| black == grid.contains(hex.into()))
+        );
+
+        let rule = |_, &black: &bool, neighbors: &[Option<&bool>]| {
+            let adjacent = neighbors.iter().flatten().filter(|&&&n| n).count();
+            adjacent == 2 || (black && adjacent == 1)
+        };
+        let mut floor = Automaton::new(grid, Hexagon::neighborhood(), rule);
+        let mut counts = Vec::new();
+        for _ in 0..10 {
+            floor.step();
+            counts.push(floor.state.len());
+        }
+        assert_eq!(vec![15, 12, 25, 14, 23, 28, 41, 37, 49, 37], counts);
+        floor.run(90);
+        assert_eq!(2208, floor.state.len());
+    }
+}
@@ -0,0 +1,346 @@
+use std::ops::RangeInclusive;
+
+use num_traits::PrimInt;
+
+/// A set of integers stored as sorted, disjoint, inclusive ranges. Ranges that overlap or touch
+/// are merged, so each gap between stored ranges holds at least one missing value.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T: PrimInt> {
+    ranges: Vec<(T, T)>,
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self { ranges: Vec::new() }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Number of disjoint ranges.
+    pub fn range_count(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn ranges(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges.iter().map(|&(start, end)| start..=end)
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|&(start, _)| start)
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.ranges.last().map(|&(_, end)| end)
+    }
+
+    /// Number of values in the set. Counted in `u128` so that sets spanning the whole of their
+    /// type still fit, saturating only for the full domain of a 128-bit type.
+    pub fn coverage(&self) -> u128 {
+        self.ranges.iter().fold(0, |acc: u128, &(start, end)| {
+            let width = match (start.to_i128(), end.to_i128()) {
+                (Some(start), Some(end)) => end.wrapping_sub(start) as u128,
+                _ => end.to_u128().expect("unsigned") - start.to_u128().expect("unsigned"),
+            };
+            acc.saturating_add(width).saturating_add(1)
+        })
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let index = self.ranges.partition_point(|&(_, end)| end < value);
+        self.ranges
+            .get(index)
+            .is_some_and(|&(start, _)| start <= value)
+    }
+
+    /// Whether every value of `range` is in the set.
+    pub fn contains_range(&self, range: RangeInclusive<T>) -> bool {
+        let (start, end) = range.into_inner();
+        if start > end {
+            return true;
+        }
+        let index = self.ranges.partition_point(|&(_, stored)| stored < start);
+        self.ranges
+            .get(index)
+            .is_some_and(|&(stored, stored_end)| stored <= start && end <= stored_end)
+    }
+
+    /// Adds every value of `range`, merging with any ranges it overlaps or touches.
+    pub fn insert(&mut self, range: RangeInclusive<T>) {
+        let (mut start, mut end) = range.into_inner();
+        if start > end {
+            return;
+        }
+        let first = self
+            .ranges
+            .partition_point(|&(_, stored)| stored.saturating_add(T::one()) < start);
+        let last = self
+            .ranges
+            .partition_point(|&(stored, _)| stored <= end.saturating_add(T::one()));
+        if first < last {
+            start = start.min(self.ranges[first].0);
+            end = end.max(self.ranges[last - 1].1);
+        }
+        self.ranges.splice(first..last, [(start, end)]);
+    }
+
+    /// Removes every value of `range`, splitting any range it falls inside.
+    pub fn remove(&mut self, range: RangeInclusive<T>) {
+        let (start, end) = range.into_inner();
+        if start > end {
+            return;
+        }
+        let first = self.ranges.partition_point(|&(_, stored)| stored < start);
+        let last = self.ranges.partition_point(|&(stored, _)| stored <= end);
+        if first >= last {
+            return;
+        }
+        let mut kept = Vec::with_capacity(2);
+        if self.ranges[first].0 < start {
+            kept.push((self.ranges[first].0, start - T::one()));
+        }
+        if self.ranges[last - 1].1 > end {
+            kept.push((end + T::one(), self.ranges[last - 1].1));
+        }
+        self.ranges.splice(first..last, kept);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        for range in other.ranges() {
+            union.insert(range);
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a_start, a_end) = self.ranges[i];
+            let (b_start, b_end) = other.ranges[j];
+            let (start, end) = (a_start.max(b_start), a_end.min(b_end));
+            if start <= end {
+                ranges.push((start, end));
+            }
+            if a_end < b_end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { ranges }
+    }
+
+    /// Values in `self` but not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut difference = self.clone();
+        for range in other.ranges() {
+            difference.remove(range);
+        }
+        difference
+    }
+
+    /// Ranges of values within `bounds` that are not in the set, in ascending order.
+    pub fn gaps(&self, bounds: RangeInclusive<T>) -> Vec<RangeInclusive<T>> {
+        Self::from(bounds).difference(self).ranges().collect()
+    }
+}
+
+impl<T: PrimInt> From<RangeInclusive<T>> for IntervalSet<T> {
+    fn from(range: RangeInclusive<T>) -> Self {
+        let mut set = Self::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl<T: PrimInt> FromIterator<RangeInclusive<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for range in iter {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+impl<T: PrimInt> Extend<RangeInclusive<T>> for IntervalSet<T> {
+    fn extend<I: IntoIterator<Item = RangeInclusive<T>>>(&mut self, iter: I) {
+        for range in iter {
+            self.insert(range);
+        }
+    }
+}
+
+/// A piecewise translation of integers: each source range is shifted so that it starts at its
+/// destination, and values outside every source range map to themselves. This is the shape of
+/// one layer of the y23d05 almanac.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RangeMap<T: PrimInt> {
+    /// `(source_start, source_end, destination_start)`, sorted by source.
+    entries: Vec<(T, T, T)>,
+}
+
+impl<T: PrimInt> RangeMap<T> {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Maps `source` onto the range starting at `destination`. Source ranges must not overlap.
+    pub fn insert(&mut self, source: RangeInclusive<T>, destination: T) {
+        let (start, end) = source.into_inner();
+        if start > end {
+            return;
+        }
+        let index = self
+            .entries
+            .partition_point(|&(stored, _, _)| stored < start);
+        debug_assert!(
+            index == 0 || self.entries[index - 1].1 < start,
+            "Source ranges must not overlap."
+        );
+        debug_assert!(
+            self.entries
+                .get(index)
+                .is_none_or(|&(stored, _, _)| end < stored),
+            "Source ranges must not overlap."
+        );
+        self.entries.insert(index, (start, end, destination));
+    }
+
+    pub fn get(&self, value: T) -> T {
+        let index = self.entries.partition_point(|&(_, end, _)| end < value);
+        match self.entries.get(index) {
+            Some(&(start, _, destination)) if start <= value => destination + (value - start),
+            _ => value,
+        }
+    }
+
+    /// The image of every value in `set`, mapping whole ranges at a time.
+    pub fn map_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut mapped = IntervalSet::new();
+        for (start, end) in set.ranges.iter().copied() {
+            let mut next = Some(start);
+            let first = self
+                .entries
+                .partition_point(|&(_, stored, _)| stored < start);
+            for &(source, source_end, destination) in &self.entries[first..] {
+                let Some(from) = next.filter(|_| source <= end) else {
+                    break;
+                };
+                if from < source {
+                    mapped.insert(from..=source - T::one());
+                }
+                let (from, last) = (from.max(source), end.min(source_end));
+                mapped.insert(destination + (from - source)..=destination + (last - source));
+                next = (last < end).then(|| last + T::one());
+            }
+            if let Some(from) = next {
+                mapped.insert(from..=end);
+            }
+        }
+        mapped
+    }
+}
+
+impl<T: PrimInt> FromIterator<(RangeInclusive<T>, T)> for RangeMap<T> {
+    fn from_iter<I: IntoIterator<Item = (RangeInclusive<T>, T)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        for (source, destination) in iter {
+            map.insert(source, destination);
+        }
+        map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blacklist() {
+        // y16d20 example
+        let blocked: IntervalSet<u32> = [5..=8, 0..=2, 4..=7].into_iter().collect();
+        assert_eq!(vec![0..=2, 4..=8], blocked.ranges().collect::<Vec<_>>());
+        assert_eq!(vec![3..=3, 9..=9], blocked.gaps(0..=9));
+        assert_eq!(8, blocked.coverage());
+        assert!(blocked.contains(4) && !blocked.contains(3));
+        assert!(blocked.contains_range(5..=8) && !blocked.contains_range(2..=4));
+
+        // touching ranges merge, including at the type's limits
+        let mut full = IntervalSet::from(0..=u32::MAX - 1);
+        full.insert(u32::MAX..=u32::MAX);
+        assert_eq!(1, full.range_count());
+        assert!(full.gaps(0..=u32::MAX).is_empty());
+        assert_eq!(1 << 32, full.coverage());
+        assert_eq!(1 << 64, IntervalSet::from(i64::MIN..=i64::MAX).coverage());
+    }
+
+    #[test]
+    fn set_operations() {
+        let a: IntervalSet<i64> = [-10..=-5, 0..=10, 20..=30].into_iter().collect();
+        let b: IntervalSet<i64> = [-7..=3, 8..=22].into_iter().collect();
+        assert_eq!(vec![-10..=30], a.union(&b).ranges().collect::<Vec<_>>());
+        assert_eq!(
+            vec![-7..=-5, 0..=3, 8..=10, 20..=22],
+            a.intersection(&b).ranges().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![-10..=-8, 4..=7, 23..=30],
+            a.difference(&b).ranges().collect::<Vec<_>>()
+        );
+
+        let mut c = a.clone();
+        c.remove(2..=4);
+        c.remove(25..=40);
+        assert_eq!(
+            vec![-10..=-5, 0..=1, 5..=10, 20..=24],
+            c.ranges().collect::<Vec<_>>()
+        );
+        assert_eq!(6 + 2 + 6 + 5, c.coverage());
+        assert_eq!((Some(-10), Some(24)), (c.min(), c.max()));
+    }
+
+    #[test]
+    fn almanac() {
+        // y23d05 example, as (destination, source, length) lines
+        let layers = [
+            vec![(50, 98, 2), (52, 50, 48)],
+            vec![(0, 15, 37), (37, 52, 2), (39, 0, 15)],
+            vec![(49, 53, 8), (0, 11, 42), (42, 0, 7), (57, 7, 4)],
+            vec![(88, 18, 7), (18, 25, 70)],
+            vec![(45, 77, 23), (81, 45, 19), (68, 64, 13)],
+            vec![(0, 69, 1), (1, 0, 69)],
+            vec![(60, 56, 37), (56, 93, 4)],
+        ];
+        let maps: Vec<RangeMap<u64>> = layers
+            .iter()
+            .map(|layer| {
+                layer
+                    .iter()
+                    .map(|&(destination, source, length)| {
+                        (source..=source + length - 1, destination)
+                    })
+                    .collect()
+            })
+            .collect();
+        let seeds = [79, 14, 55, 13];
+
+        let location = |seed| maps.iter().fold(seed, |value, map| map.get(value));
+        assert_eq!(vec![82, 43, 86, 35], seeds.map(location).to_vec());
+
+        let ranges: IntervalSet<u64> = seeds
+            .chunks(2)
+            .map(|pair| pair[0]..=pair[0] + pair[1] - 1)
+            .collect();
+        let locations = maps
+            .iter()
+            .fold(ranges.clone(), |set, map| map.map_set(&set));
+        assert_eq!(Some(46), locations.min());
+        assert_eq!(ranges.coverage(), locations.coverage());
+    }
+}
@@ -1,8 +1,13 @@
+pub mod aabb;
+pub mod bit_grid;
 pub mod coord;
 pub mod grid;
 pub mod indexer;
+pub mod interval_set;
+pub mod polygon;
 pub mod stopwatch;
 pub mod store;
 pub mod str_grid;
 pub mod hexagon;
+pub mod sparse_grid;
 pub mod union_find;
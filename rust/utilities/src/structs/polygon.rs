@@ -0,0 +1,219 @@
+use crate::{
+    enums::cardinals::Cardinal,
+    math::formulae::gcd,
+    structs::{coord::Coord2, grid::RayDirection},
+};
+
+/// Which way a polygon's vertices wind, as drawn with north up. Since north is negative y,
+/// this is the reverse of the usual mathematical convention.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Winding {
+    Clockwise,
+    CounterClockwise,
+    /// The polygon encloses no area.
+    Degenerate,
+}
+
+/// Where a point lies relative to a polygon.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PointLocation {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+/// A simple polygon with integer vertices, closed by an edge from the last vertex back to the
+/// first. Edges may run in any direction, not just along the axes.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Polygon {
+    pub vertices: Vec<Coord2>,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<Coord2>) -> Self {
+        Self { vertices }
+    }
+
+    /// Traces a polygon from `start` by moving `distance` in each direction in turn, as in a
+    /// y23d18 dig plan. The moves should end back at `start`.
+    pub fn from_moves<I>(start: Coord2, moves: I) -> Self
+    where
+        I: IntoIterator<Item = (Cardinal, i64)>,
+    {
+        let mut vertices = vec![start];
+        let mut pos = start;
+        for (dir, distance) in moves {
+            pos += dir.step() * distance;
+            vertices.push(pos);
+        }
+        if vertices.len() > 1 && vertices.last() == Some(&start) {
+            vertices.pop();
+        }
+        Self { vertices }
+    }
+
+    /// Each edge as a pair of endpoints, including the closing edge.
+    pub fn edges(&self) -> impl Iterator<Item = (Coord2, Coord2)> + '_ {
+        self.vertices
+            .iter()
+            .copied()
+            .zip(self.vertices.iter().copied().cycle().skip(1))
+    }
+
+    /// Twice the signed area, by the shoelace formula. Positive when the vertices wind
+    /// clockwise with north up.
+    pub fn doubled_signed_area(&self) -> i64 {
+        self.edges()
+            .map(|(a, b)| a.x() * b.y() - b.x() * a.y())
+            .sum()
+    }
+
+    /// Twice the area, which is always an integer for integer vertices.
+    pub fn doubled_area(&self) -> i64 {
+        self.doubled_signed_area().abs()
+    }
+
+    pub fn winding(&self) -> Winding {
+        match self.doubled_signed_area().signum() {
+            1 => Winding::Clockwise,
+            -1 => Winding::CounterClockwise,
+            _ => Winding::Degenerate,
+        }
+    }
+
+    /// Number of lattice points on the edges.
+    pub fn boundary_points(&self) -> i64 {
+        self.edges()
+            .map(|(a, b)| gcd((b.x() - a.x()).abs(), (b.y() - a.y()).abs()))
+            .sum()
+    }
+
+    /// Number of lattice points strictly inside, by Pick's theorem. This counts the tiles
+    /// enclosed by a y23d10 pipe loop.
+    pub fn interior_points(&self) -> i64 {
+        (self.doubled_area() - self.boundary_points() + 2) / 2
+    }
+
+    /// Number of lattice points inside or on the boundary, such as the cubic meters of a
+    /// y23d18 lagoon.
+    pub fn lattice_points(&self) -> i64 {
+        self.interior_points() + self.boundary_points()
+    }
+
+    pub fn locate(&self, pos: Coord2) -> PointLocation {
+        let (px, py) = (pos.x() as i128, pos.y() as i128);
+        let mut inside = false;
+        for (a, b) in self.edges() {
+            let (ax, ay, bx, by) = (a.x() as i128, a.y() as i128, b.x() as i128, b.y() as i128);
+            let cross = (bx - ax) * (py - ay) - (by - ay) * (px - ax);
+            if cross == 0
+                && ax.min(bx) <= px
+                && px <= ax.max(bx)
+                && ay.min(by) <= py
+                && py <= ay.max(by)
+            {
+                return PointLocation::Boundary;
+            }
+            // cast a ray towards positive x, counting each vertex with the edge above it
+            if (ay > py) != (by > py) {
+                let crosses_right = if by > ay { cross > 0 } else { cross < 0 };
+                if crosses_right {
+                    inside = !inside;
+                }
+            }
+        }
+        if inside {
+            PointLocation::Inside
+        } else {
+            PointLocation::Outside
+        }
+    }
+
+    /// Whether `pos` is inside the polygon or on its boundary.
+    pub fn contains(&self, pos: Coord2) -> bool {
+        self.locate(pos) != PointLocation::Outside
+    }
+}
+
+impl FromIterator<Coord2> for Polygon {
+    fn from_iter<I: IntoIterator<Item = Coord2>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lagoon() {
+        // y23d18 example
+        let plan = "R 6\nD 5\nL 2\nD 2\nR 2\nD 2\nL 5\nU 2\nL 1\nU 2\nR 2\nU 3\nL 2\nU 2";
+        let moves = plan.lines().map(|line| {
+            let (dir, distance) = line.split_once(' ').unwrap();
+            (
+                Cardinal::new(dir.chars().next().unwrap()).unwrap(),
+                distance.parse().unwrap(),
+            )
+        });
+        let lagoon = Polygon::from_moves(Coord2::origin(), moves);
+        assert_eq!(14, lagoon.vertices.len());
+        assert_eq!(38, lagoon.boundary_points());
+        assert_eq!(62, lagoon.lattice_points());
+        assert_eq!(Winding::Clockwise, lagoon.winding());
+    }
+
+    #[test]
+    fn pipe_loop() {
+        // y23d10 example with four enclosed tiles, traced from S
+        let pipes: Polygon = [
+            (1, 1),
+            (9, 1),
+            (9, 7),
+            (6, 7),
+            (6, 5),
+            (8, 5),
+            (8, 2),
+            (2, 2),
+            (2, 5),
+            (4, 5),
+            (4, 7),
+            (1, 7),
+        ]
+        .into_iter()
+        .map(Coord2::from)
+        .collect();
+        assert_eq!(46, pipes.boundary_points());
+        assert_eq!(4, pipes.interior_points());
+        assert_eq!(PointLocation::Inside, pipes.locate(Coord2::new2d(3, 6)));
+        assert_eq!(PointLocation::Outside, pipes.locate(Coord2::new2d(5, 6)));
+        assert_eq!(PointLocation::Outside, pipes.locate(Coord2::new2d(4, 3)));
+        assert_eq!(PointLocation::Boundary, pipes.locate(Coord2::new2d(1, 4)));
+        assert_eq!(PointLocation::Boundary, pipes.locate(Coord2::new2d(6, 5)));
+        assert_eq!(PointLocation::Outside, pipes.locate(Coord2::new2d(0, 0)));
+        let enclosed = (0..9)
+            .flat_map(|y| (0..11).map(move |x| Coord2::new2d(x, y)))
+            .filter(|&pos| pipes.locate(pos) == PointLocation::Inside)
+            .count();
+        assert_eq!(4, enclosed);
+    }
+
+    #[test]
+    fn diagonal_edges() {
+        let triangle = Polygon::new(vec![
+            Coord2::new2d(0, 0),
+            Coord2::new2d(0, 3),
+            Coord2::new2d(4, 0),
+        ]);
+        assert_eq!(12, triangle.doubled_area());
+        assert_eq!(8, triangle.boundary_points());
+        assert_eq!(3, triangle.interior_points());
+        assert_eq!(Winding::CounterClockwise, triangle.winding());
+        assert!(triangle.contains(Coord2::new2d(2, 1)));
+        assert_eq!(PointLocation::Outside, triangle.locate(Coord2::new2d(3, 1)));
+        assert_eq!(
+            PointLocation::Boundary,
+            triangle.locate(Coord2::new2d(4, 0))
+        );
+    }
+}
@@ -0,0 +1,318 @@
+use rustc_hash::FxHashMap;
+use std::fmt::{Display, Formatter};
+use std::ops::Index;
+
+use crate::structs::coord::Coord;
+use crate::structs::grid::{grid_display::GridDisplay, Grid};
+
+/// An unbounded grid that only stores cells differing from a background value. Suited to worlds
+/// that grow as they are simulated, where a dense `Grid` would need to be resized.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseGrid<T, const N: usize> {
+    cells: FxHashMap<Coord<i64, N>, T>,
+    background: T,
+    bounds: Option<(Coord<i64, N>, Coord<i64, N>)>,
+}
+
+pub type SparseGrid2<T> = SparseGrid<T, 2>;
+
+impl<T, const N: usize> SparseGrid<T, N> {
+    pub fn new(background: T) -> Self {
+        Self {
+            cells: FxHashMap::default(),
+            background,
+            bounds: None,
+        }
+    }
+
+    pub fn background(&self) -> &T {
+        &self.background
+    }
+
+    /// Changes the background, clearing every stored cell that now matches it and shrinking the
+    /// bounds to the cells that remain.
+    pub fn set_background(&mut self, background: T)
+    where
+        T: PartialEq,
+    {
+        self.cells.retain(|_, value| *value != background);
+        self.background = background;
+        self.shrink_bounds();
+    }
+
+    pub fn get(&self, pos: Coord<i64, N>) -> &T {
+        self.cells.get(&pos).unwrap_or(&self.background)
+    }
+
+    /// Returns the cell only if it has been explicitly stored.
+    pub fn get_stored(&self, pos: Coord<i64, N>) -> Option<&T> {
+        self.cells.get(&pos)
+    }
+
+    /// Mutable access to a stored cell. Background cells are not stored, so use `update` to
+    /// change a cell that may not exist yet. Writing the background through the reference
+    /// leaves the cell stored, so prefer `update` when that can happen.
+    pub fn get_mut(&mut self, pos: Coord<i64, N>) -> Option<&mut T> {
+        self.cells.get_mut(&pos)
+    }
+
+    /// Applies `f` to the cell at `pos`, starting from the background if nothing is stored
+    /// there, then stores the result as `insert` would, clearing the cell if it ends up as the
+    /// background.
+    pub fn update<F>(&mut self, pos: Coord<i64, N>, f: F)
+    where
+        T: Clone + PartialEq,
+        F: FnOnce(&mut T),
+    {
+        let mut value = self
+            .cells
+            .remove(&pos)
+            .unwrap_or_else(|| self.background.clone());
+        f(&mut value);
+        self.insert(pos, value);
+    }
+
+    /// Stores `value` at `pos`, or clears the cell if `value` is the background.
+    pub fn insert(&mut self, pos: Coord<i64, N>, value: T) -> Option<T>
+    where
+        T: PartialEq,
+    {
+        if value == self.background {
+            return self.cells.remove(&pos);
+        }
+        self.expand_bounds(pos);
+        self.cells.insert(pos, value)
+    }
+
+    pub fn remove(&mut self, pos: Coord<i64, N>) -> Option<T> {
+        self.cells.remove(&pos)
+    }
+
+    pub fn contains(&self, pos: Coord<i64, N>) -> bool {
+        self.cells.contains_key(&pos)
+    }
+
+    /// Number of explicitly stored cells.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Every explicitly stored cell, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Coord<i64, N>, &T)> {
+        self.cells.iter()
+    }
+
+    /// Inclusive bounding box of every cell stored so far. Removing cells does not shrink it
+    /// until `shrink_bounds` is called.
+    pub fn bounds(&self) -> Option<(Coord<i64, N>, Coord<i64, N>)> {
+        self.bounds
+    }
+
+    pub fn shrink_bounds(&mut self) {
+        self.bounds = None;
+        let positions: Vec<Coord<i64, N>> = self.cells.keys().copied().collect();
+        for pos in positions {
+            self.expand_bounds(pos);
+        }
+    }
+
+    /// Size of the bounding box along each axis.
+    pub fn dimensions(&self) -> [usize; N] {
+        match self.bounds {
+            Some((min, max)) => {
+                std::array::from_fn(|axis| (max.0[axis] - min.0[axis] + 1) as usize)
+            }
+            None => [0; N],
+        }
+    }
+
+    fn expand_bounds(&mut self, pos: Coord<i64, N>) {
+        self.bounds = Some(match self.bounds {
+            None => (pos, pos),
+            Some((mut min, mut max)) => {
+                for axis in 0..N {
+                    min.0[axis] = min.0[axis].min(pos.0[axis]);
+                    max.0[axis] = max.0[axis].max(pos.0[axis]);
+                }
+                (min, max)
+            }
+        });
+    }
+
+    /// Neighbors of `pos` along each axis, plus every diagonal if `diagonals` is set, including
+    /// background cells.
+    pub fn adjacent(
+        &self,
+        pos: Coord<i64, N>,
+        diagonals: bool,
+    ) -> impl Iterator<Item = (Coord<i64, N>, &T)> + '_ {
+        pos.neighbors_within(1, diagonals)
+            .map(move |neighbor| (neighbor, self.get(neighbor)))
+    }
+
+    /// Converts to a dense `Grid` covering the bounding box, along with the position of the
+    /// grid's origin in sparse coordinates.
+    pub fn to_grid(&self) -> Option<(Grid<T, N>, Coord<i64, N>)>
+    where
+        T: Clone,
+    {
+        let (min, _) = self.bounds?;
+        let dimensions = self.dimensions();
+        let grid = Grid::new_with_fn(dimensions, |index| {
+            let mut remainder = index;
+            let mut pos = min;
+            for (coordinate, &dimension) in pos.0.iter_mut().zip(dimensions.iter()) {
+                *coordinate += (remainder % dimension) as i64;
+                remainder /= dimension;
+            }
+            self.get(pos).clone()
+        });
+        Some((grid, min))
+    }
+
+    /// Builds a sparse grid from a dense one, storing only cells that differ from `background`.
+    /// Cell `(0, 0, ..)` of the grid lands at `offset`.
+    pub fn from_grid(grid: &Grid<T, N>, background: T, offset: Coord<i64, N>) -> Self
+    where
+        T: Clone + PartialEq,
+    {
+        let mut sparse = Self::new(background);
+        for (pos, value) in grid.iter_with_coords() {
+            let pos = offset + Coord::<i64, N>::from(pos);
+            sparse.insert(pos, value.clone());
+        }
+        sparse
+    }
+}
+
+impl<T, const N: usize> Index<Coord<i64, N>> for SparseGrid<T, N> {
+    type Output = T;
+
+    fn index(&self, index: Coord<i64, N>) -> &Self::Output {
+        self.get(index)
+    }
+}
+
+impl<T> SparseGrid<T, 2> {
+    fn display_with<F>(&self, f: &mut Formatter<'_>, to_char: F) -> std::fmt::Result
+    where
+        F: Fn(&T) -> char,
+    {
+        let Some((min, max)) = self.bounds else {
+            return Ok(());
+        };
+        let mut display = String::new();
+        for y in min.y()..=max.y() {
+            for x in min.x()..=max.x() {
+                display.push(to_char(self.get(Coord::new2d(x, y))));
+            }
+            display.push('\n');
+        }
+        display.pop();
+        write!(f, "{}", display)
+    }
+}
+
+impl Display for SparseGrid<char, 2> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.display_with(f, |&c| c)
+    }
+}
+
+impl Display for SparseGrid<bool, 2> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.display_with(f, |&on| if on { '#' } else { '.' })
+    }
+}
+
+impl<T: GridDisplay> Display for SparseGrid<T, 2> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.display_with(f, |elem| elem.rep())
+    }
+}
+
+#[test]
+fn get_and_bounds() {
+    use crate::structs::coord::Coord2;
+
+    let mut sparse = SparseGrid::new('.');
+    assert_eq!(None, sparse.bounds());
+    sparse.insert(Coord2::new2d(-2, 3), '#');
+    sparse.insert(Coord2::new2d(4, -1), '#');
+    assert_eq!(&'#', sparse.get(Coord2::new2d(-2, 3)));
+    assert_eq!('.', sparse[Coord2::new2d(100, 100)]);
+    assert_eq!(
+        Some((Coord2::new2d(-2, -1), Coord2::new2d(4, 3))),
+        sparse.bounds()
+    );
+    assert_eq!([7, 5], sparse.dimensions());
+
+    // writing the background clears the cell
+    sparse.insert(Coord2::new2d(4, -1), '.');
+    assert_eq!(1, sparse.len());
+    sparse.shrink_bounds();
+    assert_eq!(
+        Some((Coord2::new2d(-2, 3), Coord2::new2d(-2, 3))),
+        sparse.bounds()
+    );
+
+    sparse.update(Coord2::new2d(0, 0), |cell| *cell = 'x');
+    assert_eq!(&'x', sparse.get(Coord2::new2d(0, 0)));
+    *sparse.get_mut(Coord2::new2d(0, 0)).unwrap() = 'y';
+    assert_eq!(&'y', sparse.get(Coord2::new2d(0, 0)));
+
+    // reading or updating to the background stores nothing
+    assert_eq!(None, sparse.get_mut(Coord2::new2d(9, 9)));
+    sparse.update(Coord2::new2d(9, 9), |_| {});
+    sparse.update(Coord2::new2d(0, 0), |cell| *cell = '.');
+    assert_eq!(1, sparse.len());
+    assert_eq!(
+        Some((Coord2::new2d(-2, 0), Coord2::new2d(0, 3))),
+        sparse.bounds()
+    );
+
+    // a new background clears the cells that match it
+    sparse.insert(Coord2::new2d(5, 5), 'y');
+    sparse.set_background('#');
+    assert_eq!(&'#', sparse.background());
+    assert_eq!(1, sparse.len());
+    assert!(!sparse.contains(Coord2::new2d(-2, 3)));
+    assert_eq!(&'#', sparse.get(Coord2::new2d(-2, 3)));
+    assert_eq!(
+        Some((Coord2::new2d(5, 5), Coord2::new2d(5, 5))),
+        sparse.bounds()
+    );
+}
+
+#[test]
+fn adjacent() {
+    use crate::structs::coord::Coord;
+
+    let mut sparse = SparseGrid::new(false);
+    sparse.insert(Coord::new([1, 0, 0, 0]), true);
+    sparse.insert(Coord::new([1, 1, 1, 1]), true);
+    let origin = Coord::<i64, 4>::origin();
+    assert_eq!(8, sparse.adjacent(origin, false).count());
+    assert_eq!(80, sparse.adjacent(origin, true).count());
+    assert_eq!(1, sparse.adjacent(origin, false).filter(|(_, on)| **on).count());
+    assert_eq!(2, sparse.adjacent(origin, true).filter(|(_, on)| **on).count());
+}
+
+#[test]
+fn grid_round_trip_and_display() {
+    use crate::structs::coord::Coord2;
+
+    let grid = Grid::try_from(".#.\n..#\n###").unwrap();
+    let sparse = SparseGrid::from_grid(&grid, '.', Coord2::new2d(-1, -1));
+    assert_eq!(5, sparse.len());
+    assert_eq!('#', sparse[Coord2::new2d(0, -1)]);
+    assert_eq!(".#.\n..#\n###", sparse.to_string());
+
+    let (dense, origin) = sparse.to_grid().unwrap();
+    assert_eq!(grid, dense);
+    assert_eq!(Coord2::new2d(-1, -1), origin);
+}
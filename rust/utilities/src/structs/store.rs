@@ -58,6 +58,13 @@ impl<K: Hash + Eq, V> Store<K, V> {
         self.values.get_index_mut(index)
     }
 
+    /// Removes the most recently assigned entry, freeing its index for the next assignment.
+    pub fn pop(&mut self) -> Option<(K, V)> {
+        let entry = self.values.pop()?;
+        self.id -= 1;
+        Some(entry)
+    }
+
     pub fn len(&self) -> usize {
         self.id
     }
@@ -100,3 +107,16 @@ fn basic_functionality() {
     assert_eq!(2, store.get_or_assign_index(three_three, three_three));
     assert_eq!(3, store.len());
 }
+
+#[test]
+fn pop_frees_the_last_index() {
+    let mut store = Store::new();
+    assert_eq!(None, store.pop());
+    assert_eq!(Some(0), store.assign('a', 1));
+    assert_eq!(Some(1), store.assign('b', 2));
+    assert_eq!(Some(('b', 2)), store.pop());
+    assert_eq!(1, store.len());
+    assert!(!store.contains(&'b'));
+    assert_eq!(Some(1), store.assign('c', 3));
+    assert_eq!(Some((&'c', &3)), store.get_entry(1));
+}